@@ -20,10 +20,12 @@
 extern crate byteorder;
 extern crate thiserror;
 
+pub mod midi_enumeration;
 pub mod midi_event;
 pub mod midi_file;
 pub mod midi_note;
 
+pub use midi_enumeration::*;
 pub use midi_event::*;
 pub use midi_file::*;
 pub use midi_note::*;
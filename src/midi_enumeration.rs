@@ -0,0 +1,900 @@
+// midi_enumeration.rs
+//
+// Copyright (c) 2020 All The Music, LLC
+//
+// This work is licensed under the Creative Commons Attribution 4.0 International License.
+// To view a copy of this license, visit http://creativecommons.org/licenses/by/4.0/ or send
+// a letter to Creative Commons, PO Box 1866, Mountain View, CA 94042, USA.
+
+/// Error type for reading [MIDIMelodyEnumerator](struct.MIDIMelodyEnumerator.html) checkpoints
+#[derive(Debug, thiserror::Error)]
+pub enum ReadMIDIMelodyCheckpointError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid checkpoint line (expected '{expected} <value>', found {found})")]
+    InvalidLine { expected: String, found: String },
+    #[error("Unsupported checkpoint version {0}")]
+    UnsupportedVersion(String),
+    #[error(transparent)]
+    InvalidNotes(#[from] crate::midi_note::ParseMIDINoteSequenceError),
+    #[error(transparent)]
+    InvalidNumber(#[from] std::num::ParseIntError),
+    #[error("Invalid checkpoint position (range {start}..{end}, index {index})")]
+    InvalidPosition { start: u128, end: u128, index: u128 },
+}
+
+/// Error type for creating a [MIDIMelodyEnumerator](struct.MIDIMelodyEnumerator.html)
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum MIDIMelodyEnumeratorError {
+    #[error("Melody too long (found {length}, expected at most {})", MAX_MELODY_LENGTH)]
+    TooLong { length: u32 },
+    #[error("Too many melodies of length {length} drawn from {notes} notes (the number of melodies must fit in a u128)")]
+    TooManyMelodies { notes: usize, length: u32 },
+}
+
+/// Maximum number of notes in each melody of a [MIDIMelodyEnumerator](struct.MIDIMelodyEnumerator.html)
+///
+/// The number of melodies drawn from more than one note only fits in a `u128` for
+/// melodies of up to 127 notes.  Longer melodies could only be drawn from a single note.
+pub const MAX_MELODY_LENGTH: u32 = 127;
+
+/// First line of every checkpoint (followed by the checkpoint format version)
+const CHECKPOINT_HEADER: &str = "libatm-melody-checkpoint";
+
+/// Checkpoint format version
+const CHECKPOINT_VERSION: &str = "1";
+
+/// Generate the number of melodies of `length` notes drawn from `num_notes` notes
+///
+/// Returns `None` if the number of melodies does not fit in a `u128`.
+pub fn gen_melody_count(num_notes: usize, length: u32) -> Option<u128> {
+    (num_notes as u128).checked_pow(length)
+}
+
+/// Melody enumerator
+///
+/// Enumerates every melody (sequence of [MIDINote](../midi_note/struct.MIDINote.html)) of a fixed
+/// length drawn from a [MIDINoteSet](../midi_note/struct.MIDINoteSet.html), in lexicographic order.
+/// Notes are ordered by pitch (see: [MIDINote](../midi_note/struct.MIDINote.html)), and the first
+/// note of a melody is the most significant.  For example, the melodies of length 2 drawn from
+/// `C:4,D:4` are `C:4,C:4`, `C:4,D:4`, `D:4,C:4` and `D:4,D:4`.
+///
+/// The enumerator reuses a single buffer for every melody, so enumerating with
+/// [next_melody](#method.next_melody) does not allocate.  The `Iterator` implementation
+/// is provided for convenience, and allocates a `MIDINoteVec` for every melody.
+///
+/// # Examples
+///
+/// ```rust
+/// let notes = "C:4,D:4,E:4".parse::<libatm::MIDINoteSet>().unwrap();
+/// let mut melodies = libatm::MIDIMelodyEnumerator::new(&notes, 4);
+/// assert_eq!(81, melodies.total());
+/// while let Some(melody) = melodies.next_melody() {
+///     // Write MIDI file, look up melody, etc.
+///     assert_eq!(4, melody.len());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MIDIMelodyEnumerator {
+    /// Notes to draw from (ordered by pitch)
+    notes: Vec<crate::midi_note::MIDINote>,
+    /// Number of notes in each melody
+    length: u32,
+    /// Index of each note of the current melody (into `notes`)
+    digits: Vec<usize>,
+    /// Current melody
+    melody: Vec<crate::midi_note::MIDINote>,
+    /// Index of the current melody (if any)
+    current: Option<u128>,
+    /// Index of the first melody
+    start: u128,
+    /// Index of the next melody
+    index: u128,
+    /// Index after the last melody
+    end: u128,
+}
+
+impl MIDIMelodyEnumerator {
+    /// Create new `MIDIMelodyEnumerator`
+    ///
+    /// # Arguments
+    ///
+    /// * `notes`: notes to draw from
+    /// * `length`: number of notes in each melody
+    ///
+    /// # Notes
+    ///
+    /// Panics if `length` is longer than [MAX_MELODY_LENGTH](constant.MAX_MELODY_LENGTH.html), or if
+    /// the number of melodies does not fit in a `u128` (see: [try_new](#method.try_new)).
+    pub fn new(notes: &crate::midi_note::MIDINoteSet, length: u32) -> Self {
+        match Self::try_new(notes, length) {
+            Ok(melodies) => melodies,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Create new `MIDIMelodyEnumerator`, failing instead of panicking (see: [new](#method.new))
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let notes = "C:-1..G:9".parse::<libatm::MIDINoteSet>().unwrap();
+    /// assert!(libatm::MIDIMelodyEnumerator::try_new(&notes, 18).is_ok());
+    /// // 128^19 melodies do not fit in a u128
+    /// assert_eq!(
+    ///     Err(libatm::MIDIMelodyEnumeratorError::TooManyMelodies { notes: 128, length: 19 }),
+    ///     libatm::MIDIMelodyEnumerator::try_new(&notes, 19).map(|melodies| melodies.total()),
+    /// );
+    /// ```
+    pub fn try_new(notes: &crate::midi_note::MIDINoteSet, length: u32) -> Result<Self, MIDIMelodyEnumeratorError> {
+        if length > MAX_MELODY_LENGTH {
+            return Err(MIDIMelodyEnumeratorError::TooLong { length });
+        }
+        let total = gen_melody_count(notes.len(), length)
+            .ok_or(MIDIMelodyEnumeratorError::TooManyMelodies { notes: notes.len(), length })?;
+        let notes = notes.iter().copied().collect::<Vec<crate::midi_note::MIDINote>>();
+        let melody = match notes.first() {
+            Some(note) => vec![*note; length as usize],
+            None => Vec::new(),
+        };
+        Ok(Self {
+            notes,
+            length,
+            digits: vec![0; melody.len()],
+            melody,
+            current: None,
+            start: 0,
+            index: 0,
+            end: total,
+        })
+    }
+
+    /// Create new `MIDIMelodyEnumerator` over a range of indices
+    ///
+    /// Only the melodies with an index in `range` are enumerated (see: [rank](#method.rank)).
+    ///
+    /// # Notes
+    ///
+    /// Panics if `range` ends after the last melody.
+    pub fn with_range(notes: &crate::midi_note::MIDINoteSet, length: u32, range: std::ops::Range<u128>) -> Self {
+        let mut melodies = Self::new(notes, length);
+        // 0 <= start <= end <= total
+        assert!(range.start <= range.end && range.end <= melodies.end);
+        melodies.start = range.start;
+        melodies.index = range.start;
+        melodies.end = range.end;
+        melodies
+    }
+
+    /// Number of melodies (including melodies already enumerated)
+    pub fn total(&self) -> u128 {
+        // Checked when the enumerator is created
+        gen_melody_count(self.notes.len(), self.length).unwrap()
+    }
+
+    /// Number of melodies left to enumerate
+    pub fn remaining(&self) -> u128 {
+        self.end - self.index
+    }
+
+    /// Index of the next melody
+    pub fn index(&self) -> u128 {
+        self.index
+    }
+
+    /// Range of indices enumerated (see: [with_range](#method.with_range))
+    pub fn range(&self) -> std::ops::Range<u128> {
+        self.start..self.end
+    }
+
+    /// Move to the melody at `index`, so it is the next melody enumerated
+    ///
+    /// # Notes
+    ///
+    /// Panics if `index` is outside of the range of indices enumerated.
+    pub fn seek(&mut self, index: u128) {
+        // start <= index <= end
+        assert!(self.start <= index && index <= self.end);
+        self.index = index;
+    }
+
+    /// Create enumerator over one of `num_shards` disjoint shards of this enumerator's range
+    ///
+    /// The range is split into contiguous shards of (nearly) equal size, so the shards cover
+    /// every melody exactly once.  Shards only depend on the range, the shard and the number of
+    /// shards (not on how many melodies have already been enumerated), so separate processes or
+    /// machines can each enumerate their own shard deterministically.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let notes = "C:4,D:4,E:4".parse::<libatm::MIDINoteSet>().unwrap();
+    /// let melodies = libatm::MIDIMelodyEnumerator::new(&notes, 4);
+    /// assert_eq!(0..21, melodies.shard(0, 4).range());
+    /// assert_eq!(21..41, melodies.shard(1, 4).range());
+    /// assert_eq!(61..81, melodies.shard(3, 4).range());
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// Panics if `shard` is not less than `num_shards`.
+    pub fn shard(&self, shard: usize, num_shards: usize) -> Self {
+        // 0 <= shard < num_shards
+        assert!(shard < num_shards);
+        let range = split_range(self.range(), shard, num_shards);
+        let mut melodies = self.clone();
+        melodies.start = range.start;
+        melodies.index = range.start;
+        melodies.end = range.end;
+        melodies
+    }
+
+    /// Split this enumerator's range into `num_shards` shards (see: [shard](#method.shard))
+    pub fn shards(&self, num_shards: usize) -> Vec<Self> {
+        (0..num_shards).map(|shard| self.shard(shard, num_shards)).collect::<Vec<Self>>()
+    }
+
+    /// Enumerate remaining melodies across `num_threads` threads
+    ///
+    /// The remaining melodies are split into one shard per thread, and `f` is called with the
+    /// index and notes of every melody.  Melodies within a shard are enumerated in order, but
+    /// shards are enumerated concurrently.  If `f` returns an error, every thread stops enumerating
+    /// its shard before the next melody, and the first error (in shard order) is returned once all
+    /// threads are done.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let notes = "C:4,D:4,E:4,F:4".parse::<libatm::MIDINoteSet>().unwrap();
+    /// let melodies = libatm::MIDIMelodyEnumerator::new(&notes, 6);
+    /// melodies.for_each_parallel(4, |_index, melody| {
+    ///     let mfile = libatm::MIDIFile::new(
+    ///         libatm::MIDINoteVec(melody.to_vec()),
+    ///         libatm::MIDIFormat::Format0,
+    ///         1,
+    ///     );
+    ///     // Write file to disk (i.e. with MIDIFile::write_file)
+    ///     mfile.write_buffer(&mut std::io::sink())
+    /// }).unwrap();
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// Panics if `num_threads` is 0, or if `f` panics.
+    pub fn for_each_parallel<F, E>(&self, num_threads: usize, f: F) -> Result<(), E>
+    where
+        F: Fn(u128, &[crate::midi_note::MIDINote]) -> Result<(), E> + Sync,
+        E: Send,
+    {
+        // 0 < num_threads
+        assert!(num_threads > 0);
+        let mut remaining = self.clone();
+        remaining.start = self.index;
+        let f = &f;
+        // Set by the first worker to fail, so the others stop early
+        let stop = &std::sync::atomic::AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            let workers = remaining
+                .shards(num_threads)
+                .into_iter()
+                .map(|mut melodies| {
+                    scope.spawn(move || {
+                        while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                            let index = melodies.index();
+                            match melodies.next_melody() {
+                                Some(melody) => {
+                                    if let Err(err) = f(index, melody) {
+                                        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+                                        return Err(err);
+                                    }
+                                },
+                                None => break,
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect::<Vec<std::thread::ScopedJoinHandle<Result<(), E>>>>();
+            // Join every worker before returning the first error
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap_or_else(|err| std::panic::resume_unwind(err)))
+                .collect::<Vec<Result<(), E>>>()
+                .into_iter()
+                .collect::<Result<(), E>>()
+        })
+    }
+
+    /// Generate index of `melody` in the enumeration (A.K.A. rank)
+    ///
+    /// Returns `None` if `melody` does not have the same length as the
+    /// melodies enumerated, or contains a note not in the note set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let notes = "C:4,D:4,E:4".parse::<libatm::MIDINoteSet>().unwrap();
+    /// let mut melodies = libatm::MIDIMelodyEnumerator::new(&notes, 4);
+    /// let melody = "E:4,C:4,D:4,C:4".parse::<libatm::MIDINoteVec>().unwrap();
+    /// let index = melodies.rank(&melody).unwrap();
+    /// assert_eq!(57, index);
+    /// assert_eq!(Some(melody.clone()), melodies.unrank(index));
+    /// // Jump straight to the melody
+    /// melodies.seek(index);
+    /// assert_eq!(Some(melody.as_slice()), melodies.next_melody());
+    /// ```
+    pub fn rank(&self, melody: &[crate::midi_note::MIDINote]) -> Option<u128> {
+        if melody.len() != self.length as usize {
+            return None;
+        }
+        melody.iter().try_fold(0u128, |index, note| {
+            let digit = self.notes.binary_search(note).ok()?;
+            Some(index * self.notes.len() as u128 + digit as u128)
+        })
+    }
+
+    /// Generate melody at `index` in the enumeration (A.K.A. unrank)
+    ///
+    /// Returns `None` if `index` is not less than the number of melodies.
+    pub fn unrank(&self, index: u128) -> Option<crate::midi_note::MIDINoteVec> {
+        if index >= self.total() {
+            return None;
+        }
+        let mut melody = self.melody.clone();
+        let mut digits = self.digits.clone();
+        gen_melody(&self.notes, index, &mut digits, &mut melody);
+        Some(crate::midi_note::MIDINoteVec(melody))
+    }
+
+    /// Generate index of the melody with hash `hash` (see: [MIDIFile::gen_hash](../midi_file/struct.MIDIFile.html#method.gen_hash))
+    ///
+    /// Allows finding the file containing a melody by index, when files are
+    /// grouped by index (i.e. one directory per million melodies).  Hashes do not record
+    /// the octave of rests, so rests match the (first) rest of the note set regardless of octave.
+    /// Returns `None` if the hash cannot be decoded or the melody is not part of the enumeration.
+    pub fn rank_hash(&self, hash: &str) -> Option<u128> {
+        let mut melody = crate::midi_file::MIDIFile::decode_hash(hash).ok()?;
+        if let Some(rest) = self.notes.iter().find(|note| note.note_type == crate::midi_note::MIDINoteType::Rest) {
+            for note in melody.0.iter_mut().filter(|note| note.note_type == crate::midi_note::MIDINoteType::Rest) {
+                *note = *rest;
+            }
+        }
+        self.rank(&melody)
+    }
+
+    /// Generate the next melody
+    ///
+    /// The returned slice is only valid until the next call, which
+    /// overwrites it with the next melody.
+    pub fn next_melody(&mut self) -> Option<&[crate::midi_note::MIDINote]> {
+        if self.index >= self.end {
+            return None;
+        }
+        match self.current {
+            // Only update notes that changed since the previous melody
+            Some(current) if current + 1 == self.index => self.increment(),
+            _ => gen_melody(&self.notes, self.index, &mut self.digits, &mut self.melody),
+        }
+        self.current = Some(self.index);
+        self.index += 1;
+        Some(&self.melody)
+    }
+
+    /// Enumerate remaining melodies as single track `MIDIFile`s
+    pub fn into_files(
+        self,
+        format: crate::midi_file::MIDIFormat,
+        division: u16,
+    ) -> impl Iterator<Item=crate::midi_file::MIDIFile> {
+        self.map(move |melody| crate::midi_file::MIDIFile::new(melody, format, division))
+    }
+
+    /// Generate checkpoint of the enumeration position
+    ///
+    /// The checkpoint is a small text file containing the note set, the melody length, the
+    /// range of indices enumerated (i.e. the shard) and the index of the next melody.  An
+    /// enumerator created from the checkpoint (see: [from_checkpoint](#method.from_checkpoint))
+    /// continues with exactly the melody this enumerator would enumerate next.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let notes = "C:4,D:4,E:4".parse::<libatm::MIDINoteSet>().unwrap();
+    /// let mut melodies = libatm::MIDIMelodyEnumerator::new(&notes, 4).shard(1, 2);
+    /// melodies.next_melody();
+    /// let checkpoint = melodies.gen_checkpoint();
+    /// assert_eq!(
+    ///     "libatm-melody-checkpoint 1\nnotes C:4,D:4,E:4\nlength 4\nrange 41 81\nindex 42\n",
+    ///     checkpoint,
+    /// );
+    /// let mut resumed = libatm::MIDIMelodyEnumerator::from_checkpoint(&checkpoint).unwrap();
+    /// assert_eq!(melodies.next_melody(), resumed.next_melody());
+    /// ```
+    pub fn gen_checkpoint(&self) -> String {
+        format!(
+            "{} {}\nnotes {}\nlength {}\nrange {} {}\nindex {}\n",
+            CHECKPOINT_HEADER,
+            CHECKPOINT_VERSION,
+            crate::midi_note::MIDINoteVec(self.notes.clone()),
+            self.length,
+            self.start,
+            self.end,
+            self.index,
+        )
+    }
+
+    /// Create `MIDIMelodyEnumerator` from checkpoint (see: [gen_checkpoint](#method.gen_checkpoint))
+    pub fn from_checkpoint(checkpoint: &str) -> Result<Self, ReadMIDIMelodyCheckpointError> {
+        let mut lines = checkpoint.lines();
+        let mut read_field = |expected: &str| {
+            let line = lines.next().unwrap_or("");
+            match line.strip_prefix(expected).and_then(|value| value.strip_prefix(' ')) {
+                Some(value) => Ok(value),
+                None => Err(ReadMIDIMelodyCheckpointError::InvalidLine {
+                    expected: expected.to_string(),
+                    found: line.to_string(),
+                }),
+            }
+        };
+        let version = read_field(CHECKPOINT_HEADER)?;
+        if version != CHECKPOINT_VERSION {
+            return Err(ReadMIDIMelodyCheckpointError::UnsupportedVersion(version.to_string()));
+        }
+        let notes = read_field("notes")?.parse::<crate::midi_note::MIDINoteSet>()?;
+        let length = read_field("length")?.parse::<u32>()?;
+        let range = read_field("range")?;
+        let (start, end) = range.split_once(' ').ok_or_else(|| ReadMIDIMelodyCheckpointError::InvalidLine {
+            expected: String::from("range"),
+            found: format!("range {}", range),
+        })?;
+        let (start, end) = (start.parse::<u128>()?, end.parse::<u128>()?);
+        let index = read_field("index")?.parse::<u128>()?;
+
+        // Ensure position is part of the enumeration
+        let invalid_position = ReadMIDIMelodyCheckpointError::InvalidPosition { start, end, index };
+        let mut melodies = match Self::try_new(&notes, length) {
+            Ok(melodies) => melodies,
+            Err(_) => return Err(invalid_position),
+        };
+        if end > melodies.end || start > end || index < start || index > end {
+            return Err(invalid_position);
+        }
+        melodies.start = start;
+        melodies.end = end;
+        melodies.seek(index);
+        Ok(melodies)
+    }
+
+    /// Write checkpoint to path on disk (see: [gen_checkpoint](#method.gen_checkpoint))
+    ///
+    /// The checkpoint is first written to a temporary file (`path` followed by `.tmp`)
+    /// and then renamed, so `path` always contains a complete checkpoint even if the process
+    /// is interrupted while writing.  On Unix, the directory containing `path` is synced after the
+    /// rename so the new checkpoint survives a crash.  On other platforms, the rename is not guaranteed
+    /// to be durable (`path` may still contain the previous checkpoint after a crash).  To guarantee
+    /// no melody is skipped, only write a checkpoint
+    /// once every melody returned by [next_melody](#method.next_melody) has been processed.
+    pub fn write_checkpoint<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+        {
+            let mut target_file = std::fs::File::create(&temp_path)?;
+            std::io::Write::write_all(&mut target_file, self.gen_checkpoint().as_bytes())?;
+            target_file.sync_all()?;
+        }
+        std::fs::rename(temp_path, path)?;
+        #[cfg(unix)]
+        {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => std::path::Path::new("."),
+            };
+            std::fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Read checkpoint from path on disk (see: [from_checkpoint](#method.from_checkpoint))
+    pub fn read_checkpoint<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ReadMIDIMelodyCheckpointError> {
+        Self::from_checkpoint(&std::fs::read_to_string(path)?)
+    }
+
+    /// Advance current melody to the next melody
+    fn increment(&mut self) {
+        for position in (0..self.digits.len()).rev() {
+            self.digits[position] += 1;
+            if self.digits[position] < self.notes.len() {
+                self.melody[position] = self.notes[self.digits[position]];
+                return;
+            }
+            // Carry to previous position
+            self.digits[position] = 0;
+            self.melody[position] = self.notes[0];
+        }
+    }
+
+}
+
+/// Split `range` into `num_shards` contiguous ranges of (nearly) equal size, and return range `shard`
+fn split_range(range: std::ops::Range<u128>, shard: usize, num_shards: usize) -> std::ops::Range<u128> {
+    let length = range.end - range.start;
+    let (shard, num_shards) = (shard as u128, num_shards as u128);
+    // The first (length % num_shards) shards have one extra melody
+    let (size, extra) = (length / num_shards, length % num_shards);
+    let start = range.start + shard * size + shard.min(extra);
+    let end = start + size + if shard < extra { 1 } else { 0 };
+    start..end
+}
+
+/// Generate melody at `index` (and the index of each of its notes into `notes`)
+fn gen_melody(
+    notes: &[crate::midi_note::MIDINote],
+    mut index: u128,
+    digits: &mut [usize],
+    melody: &mut [crate::midi_note::MIDINote],
+) {
+    let num_notes = notes.len() as u128;
+    for position in (0..digits.len()).rev() {
+        digits[position] = (index % num_notes) as usize;
+        melody[position] = notes[digits[position]];
+        index /= num_notes;
+    }
+}
+
+impl Iterator for MIDIMelodyEnumerator {
+    type Item = crate::midi_note::MIDINoteVec;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_melody().map(|melody| crate::midi_note::MIDINoteVec(melody.to_vec()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match std::convert::TryFrom::try_from(self.remaining()) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_test_notes(notes: &str) -> crate::midi_note::MIDINoteSet {
+        notes.parse::<crate::midi_note::MIDINoteSet>().unwrap()
+    }
+
+    #[test]
+    fn test_gen_melody_count() {
+        assert_eq!(Some(1), gen_melody_count(0, 0));
+        assert_eq!(Some(0), gen_melody_count(0, 3));
+        assert_eq!(Some(8u128.pow(12)), gen_melody_count(8, 12));
+        assert_eq!(None, gen_melody_count(128, 20));
+    }
+
+    #[test]
+    fn test_enumerate_order() {
+        // Notes are ordered by pitch, regardless of input order
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("D:4,C:4"), 2)
+            .map(|melody| melody.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["C:4,C:4", "C:4,D:4", "D:4,C:4", "D:4,D:4"], melodies);
+    }
+
+    #[test]
+    fn test_enumerate_count() {
+        let mut melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4,E:4,F:4,G:4"), 4);
+        assert_eq!(625, melodies.total());
+        assert_eq!((625, Some(625)), melodies.size_hint());
+        let mut count = 0;
+        let mut previous: Option<Vec<crate::midi_note::MIDINote>> = None;
+        while let Some(melody) = melodies.next_melody() {
+            // Strictly increasing (lexicographic) order
+            if let Some(previous) = previous {
+                assert!(previous.as_slice() < melody);
+            }
+            previous = Some(melody.to_vec());
+            count += 1;
+        }
+        assert_eq!(625, count);
+        assert_eq!(0, melodies.remaining());
+        assert_eq!(None, melodies.next_melody());
+    }
+
+    #[test]
+    fn test_enumerate_empty() {
+        assert_eq!(0, MIDIMelodyEnumerator::new(&gen_test_notes(""), 3).count());
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4"), 0).collect::<Vec<_>>();
+        assert_eq!(vec![crate::midi_note::MIDINoteVec(Vec::new())], melodies);
+    }
+
+    #[test]
+    fn test_enumerate_empty_notes() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes(""), 3);
+        assert_eq!(0, melodies.total());
+        assert_eq!(0, melodies.remaining());
+        assert_eq!(None, melodies.unrank(0));
+    }
+
+    #[test]
+    fn test_enumerate_too_long() {
+        assert_eq!(
+            Err(MIDIMelodyEnumeratorError::TooLong { length: 4_000_000_000 }),
+            MIDIMelodyEnumerator::try_new(&gen_test_notes("C:4"), 4_000_000_000).map(|melodies| melodies.total()),
+        );
+        assert_eq!(
+            Err(MIDIMelodyEnumeratorError::TooLong { length: MAX_MELODY_LENGTH + 1 }),
+            MIDIMelodyEnumerator::try_new(&gen_test_notes(""), MAX_MELODY_LENGTH + 1).map(|melodies| melodies.total()),
+        );
+        let melodies = MIDIMelodyEnumerator::try_new(&gen_test_notes("C:4,D:4"), MAX_MELODY_LENGTH).unwrap();
+        assert_eq!(1 << MAX_MELODY_LENGTH, melodies.total());
+    }
+
+    #[test]
+    fn test_enumerate_files() {
+        let files = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4"), 3)
+            .into_files(crate::midi_file::MIDIFormat::Format0, 1)
+            .map(|mfile| mfile.gen_hash().unwrap())
+            .collect::<Vec<String>>();
+        assert_eq!(8, files.len());
+        assert_eq!("60-60-60", files[0]);
+        assert_eq!("60-62-60", files[2]);
+        assert_eq!("62-62-62", files[7]);
+    }
+
+    #[test]
+    fn test_rank_unrank() {
+        let mut melodies = MIDIMelodyEnumerator::new(&gen_test_notes("Rest:0,C:4,E:4,G:4"), 3);
+        let expected = melodies.clone().collect::<Vec<crate::midi_note::MIDINoteVec>>();
+        for (index, melody) in expected.iter().enumerate() {
+            assert_eq!(Some(index as u128), melodies.rank(melody));
+            assert_eq!(Some(melody.clone()), melodies.unrank(index as u128));
+        }
+        assert_eq!(None, melodies.unrank(64));
+        assert_eq!(None, melodies.rank(&"C:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap()));
+        assert_eq!(None, melodies.rank(&"C:4,E:4,D:4".parse::<crate::midi_note::MIDINoteVec>().unwrap()));
+
+        // Seek backwards and forwards
+        melodies.seek(40);
+        assert_eq!(Some(expected[40].as_slice()), melodies.next_melody());
+        assert_eq!(Some(expected[41].as_slice()), melodies.next_melody());
+        melodies.seek(3);
+        assert_eq!(3, melodies.index());
+        assert_eq!(61, melodies.remaining());
+        assert_eq!(expected[3..].to_vec(), melodies.collect::<Vec<crate::midi_note::MIDINoteVec>>());
+    }
+
+    #[test]
+    fn test_rank_hash() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4..B:4"), 5);
+        let melody = "B:4,D:4,C:4,F#:4,A#:4".parse::<crate::midi_note::MIDINoteVec>().unwrap();
+        let mfile = crate::midi_file::MIDIFile::new(melody.clone(), crate::midi_file::MIDIFormat::Format0, 1);
+        let index = melodies.rank_hash(&mfile.gen_hash().unwrap()).unwrap();
+        assert_eq!(11 * 12u128.pow(4) + 2 * 12u128.pow(3) + 6 * 12 + 10, index);
+        assert_eq!(Some(melody), melodies.unrank(index));
+        assert_eq!(None, melodies.rank_hash("60-62"));
+        assert_eq!(None, melodies.rank_hash("invalid"));
+    }
+
+    #[test]
+    fn test_rank_hash_rests() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("Rest:4,C:4"), 2);
+        for (index, melody) in melodies.clone().enumerate() {
+            let mfile = crate::midi_file::MIDIFile::new(melody, crate::midi_file::MIDIFormat::Format0, 1);
+            assert_eq!(Some(index as u128), melodies.rank_hash(&mfile.gen_hash().unwrap()));
+        }
+        assert_eq!(Some(1), melodies.rank_hash("r-60"));
+        assert_eq!(None, MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4"), 2).rank_hash("r-60"));
+    }
+
+    #[test]
+    fn test_shards() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4,E:4"), 5);
+        let expected = melodies.clone().collect::<Vec<crate::midi_note::MIDINoteVec>>();
+        for num_shards in 1..=10 {
+            let observed = melodies
+                .shards(num_shards)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<crate::midi_note::MIDINoteVec>>();
+            assert_eq!(expected, observed);
+        }
+        // Shards of shards
+        let shard = melodies.shard(1, 2);
+        assert_eq!(122..243, shard.range());
+        assert_eq!(vec![122..163, 163..203, 203..243], shard.shards(3).iter().map(|shard| shard.range()).collect::<Vec<_>>());
+        // More shards than melodies
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4"), 1);
+        let ranges = melodies.shards(3).iter().map(|shard| shard.range()).collect::<Vec<_>>();
+        assert_eq!(vec![0..1, 1..2, 2..2], ranges);
+    }
+
+    #[test]
+    fn test_with_range() {
+        let notes = gen_test_notes("C:4,D:4,E:4");
+        let expected = MIDIMelodyEnumerator::new(&notes, 3).collect::<Vec<crate::midi_note::MIDINoteVec>>();
+        let mut melodies = MIDIMelodyEnumerator::with_range(&notes, 3, 5..9);
+        assert_eq!(27, melodies.total());
+        assert_eq!(4, melodies.remaining());
+        assert_eq!(Some(expected[5].as_slice()), melodies.next_melody());
+        assert_eq!(expected[6..9].to_vec(), melodies.collect::<Vec<crate::midi_note::MIDINoteVec>>());
+    }
+
+    #[test]
+    fn test_split_range_large() {
+        let range = 0..u128::MAX;
+        assert_eq!(0..u128::MAX / 2 + 1, split_range(range.clone(), 0, 2));
+        assert_eq!(u128::MAX / 2 + 1..u128::MAX, split_range(range, 1, 2));
+    }
+
+    #[test]
+    fn test_for_each_parallel() {
+        let mut melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4,E:4,F:4"), 5);
+        melodies.seek(100);
+        let observed = std::sync::Mutex::new(Vec::new());
+        melodies
+            .for_each_parallel(3, |index, melody| {
+                assert_eq!(Some(index), melodies.rank(melody));
+                observed.lock().unwrap().push(index);
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        let mut observed = observed.into_inner().unwrap();
+        observed.sort_unstable();
+        assert_eq!((100..1024).collect::<Vec<u128>>(), observed);
+    }
+
+    #[test]
+    fn test_for_each_parallel_error() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4,E:4,F:4"), 4);
+        let observed = melodies.for_each_parallel(4, |index, _| match index {
+            70 | 200 => Err(index),
+            _ => Ok(()),
+        });
+        assert!(observed == Err(70) || observed == Err(200));
+    }
+
+    #[test]
+    fn test_for_each_parallel_stop() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4,E:4,F:4"), 10);
+        let failed = std::sync::atomic::AtomicBool::new(false);
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let observed = melodies.for_each_parallel(2, |index, _| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if index == 0 {
+                failed.store(true, std::sync::atomic::Ordering::SeqCst);
+                return Err(index);
+            }
+            // Hold the other shard until the first one fails
+            while !failed.load(std::sync::atomic::Ordering::SeqCst) {
+                std::thread::yield_now();
+            }
+            Ok(())
+        });
+        assert_eq!(Err(0), observed);
+        assert!(calls.into_inner() < 1000);
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let notes = gen_test_notes("Rest:0,C:4,D#:4,G:4");
+        let expected = MIDIMelodyEnumerator::new(&notes, 4).shard(2, 3).collect::<Vec<crate::midi_note::MIDINoteVec>>();
+        for processed in 0..=expected.len() {
+            let mut melodies = MIDIMelodyEnumerator::new(&notes, 4).shard(2, 3);
+            let mut observed = Vec::new();
+            for _ in 0..processed {
+                observed.push(crate::midi_note::MIDINoteVec(melodies.next_melody().unwrap().to_vec()));
+            }
+            let checkpoint = melodies.gen_checkpoint();
+            let resumed = MIDIMelodyEnumerator::from_checkpoint(&checkpoint).unwrap();
+            assert_eq!(melodies.range(), resumed.range());
+            observed.extend(resumed);
+            assert_eq!(expected, observed);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_file() {
+        let path = std::env::temp_dir().join(format!("libatm-checkpoint-{}", std::process::id()));
+        let mut melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4"), 3);
+        melodies.seek(5);
+        melodies.write_checkpoint(&path).unwrap();
+        assert!(!path.with_file_name(format!("libatm-checkpoint-{}.tmp", std::process::id())).exists());
+        let mut resumed = MIDIMelodyEnumerator::read_checkpoint(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(5, resumed.index());
+        assert_eq!(melodies.next_melody(), resumed.next_melody());
+    }
+
+    #[test]
+    fn test_checkpoint_file_extension() {
+        let dir = std::env::temp_dir();
+        let paths = (0..2)
+            .map(|shard| dir.join(format!("libatm-checkpoint-{}.{}", std::process::id(), shard)))
+            .collect::<Vec<std::path::PathBuf>>();
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4"), 3).shards(2);
+        for (shard, path) in melodies.iter().zip(paths.iter()) {
+            shard.write_checkpoint(path).unwrap();
+        }
+        for (shard, path) in melodies.iter().zip(paths.iter()) {
+            let resumed = MIDIMelodyEnumerator::read_checkpoint(path).unwrap();
+            std::fs::remove_file(path).unwrap();
+            assert_eq!(shard.range(), resumed.range());
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_empty_notes() {
+        let melodies = MIDIMelodyEnumerator::new(&gen_test_notes(""), 3);
+        let checkpoint = melodies.gen_checkpoint();
+        assert_eq!("libatm-melody-checkpoint 1\nnotes \nlength 3\nrange 0 0\nindex 0\n", checkpoint);
+        let resumed = MIDIMelodyEnumerator::from_checkpoint(&checkpoint).unwrap();
+        assert_eq!(checkpoint, resumed.gen_checkpoint());
+        assert_eq!(0, resumed.total());
+    }
+
+    #[test]
+    fn test_checkpoint_invalid() {
+        let cases = vec![
+            "",
+            "libatm-melody-checkpoint 1\nnotes C:4\nrange 0 1\nindex 0\n",
+            "libatm-melody-checkpoint 1\nnotes C:4\nlength 1\nrange 0\nindex 0\n",
+            "libatm-melody-checkpoint 1\nnotes C:4\nlength 1\nrange 0 1\n",
+        ];
+        for checkpoint in cases.into_iter() {
+            let observed = MIDIMelodyEnumerator::from_checkpoint(checkpoint);
+            assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::InvalidLine { .. })), "{}", checkpoint);
+        }
+        let observed = MIDIMelodyEnumerator::from_checkpoint(
+            "libatm-melody-checkpoint 2\nnotes C:4\nlength 1\nrange 0 1\nindex 0\n",
+        );
+        assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::UnsupportedVersion(_))));
+        let observed = MIDIMelodyEnumerator::from_checkpoint(
+            "libatm-melody-checkpoint 1\nnotes C:4,X:4\nlength 1\nrange 0 1\nindex 0\n",
+        );
+        assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::InvalidNotes(_))));
+        let observed = MIDIMelodyEnumerator::from_checkpoint(
+            "libatm-melody-checkpoint 1\nnotes C:4\nlength -1\nrange 0 1\nindex 0\n",
+        );
+        assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::InvalidNumber(_))));
+        for (range, index) in vec![("0 9", 0), ("2 1", 2), ("2 4", 1), ("2 4", 5)].into_iter() {
+            let observed = MIDIMelodyEnumerator::from_checkpoint(&format!(
+                "libatm-melody-checkpoint 1\nnotes C:4,D:4\nlength 3\nrange {}\nindex {}\n",
+                range,
+                index,
+            ));
+            assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::InvalidPosition { .. })));
+        }
+        let observed = MIDIMelodyEnumerator::from_checkpoint(
+            "libatm-melody-checkpoint 1\nnotes C:4..C:6\nlength 40\nrange 0 1\nindex 0\n",
+        );
+        assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::InvalidPosition { .. })));
+        let observed = MIDIMelodyEnumerator::from_checkpoint(
+            "libatm-melody-checkpoint 1\nnotes C:4\nlength 4000000000\nrange 0 1\nindex 0\n",
+        );
+        assert!(matches!(observed, Err(ReadMIDIMelodyCheckpointError::InvalidPosition { .. })));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_seek_out_of_range() {
+        let mut melodies = MIDIMelodyEnumerator::new(&gen_test_notes("C:4,D:4"), 2);
+        melodies.seek(5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_enumerate_too_many() {
+        let _ = MIDIMelodyEnumerator::new(&gen_test_notes("C:4..C:6"), 40);
+    }
+
+    #[test]
+    fn test_try_new_too_many() {
+        assert_eq!(
+            Err(MIDIMelodyEnumeratorError::TooManyMelodies { notes: 25, length: 40 }),
+            MIDIMelodyEnumerator::try_new(&gen_test_notes("C:4..C:6"), 40).map(|melodies| melodies.total()),
+        );
+    }
+}
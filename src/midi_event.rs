@@ -38,14 +38,14 @@ pub enum MIDIStatus {
 /// MIDI supports two main types of messages: Channel and System.
 /// Channel messages are tied to a specific MIDI channel, whereas
 /// System messages are not (and thus don't contain a channel number).
-/// This library only supports channel messages, and more specifically
-/// the `NoteOn` and `NoteOff` channel _voice_ messages,
-/// which actually produce sounds.  For a detailed explanation of
+/// This type only supports the `NoteOn` and `NoteOff` channel _voice_ messages,
+/// which actually produce sounds (see [MIDIChannelMessage](enum.MIDIChannelMessage.html)
+/// for other channel messages).  For a detailed explanation of
 /// MIDI messages, see appendix 1.1 of the document here:
 /// <https://www.cs.cmu.edu/~music/cmsip/readings/Standard-MIDI-file-format-updated.pdf>.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct MIDIChannelVoiceMessage {
-    pub delta_time: u8,
+    pub delta_time: u32,
     pub status: u8,
     pub note: u8,
     pub velocity: u8,
@@ -56,7 +56,7 @@ impl MIDIChannelVoiceMessage {
     ///
     /// # Arguments
     ///
-    /// * `delta_time`: time delta since last MIDI channel message
+    /// * `delta_time`: time delta since last MIDI channel message (at most `0x0FFFFFFF`)
     /// * `note`: [MIDINote](../midi_note/struct.MIDINote.html) to play
     /// * `velocity`: velocity with which to play the note
     /// * `status`: [MIDIStatus](enum.MIDIStatus.html) bits of the message
@@ -81,23 +81,28 @@ impl MIDIChannelVoiceMessage {
     ///   possible MIDI files.
     /// * If the note type is [MIDINoteType::Rest](../midi_note/enum.MIDINoteType.html#variant.Rest)
     ///   then the velocity will automatically be set to 0 (equivalent to a `NoteOff` event).
+    /// * Panics if the note is outside of the MIDI note range.
     pub fn new(
-        delta_time: u8,
+        delta_time: u32,
         note: &crate::midi_note::MIDINote,
         velocity: u8,
         status: MIDIStatus,
         channel: u8,
     ) -> MIDIChannelVoiceMessage {
+        // 0 <= delta_time <= 0x0FFFFFFF (largest 4-byte variable-length quantity)
+        assert!(delta_time <= MAX_VLQ);
         // 0 <= channel < 0x10 (16)
         assert!(channel < 0x10);
         // 0 <= velocity < 0x80 (128)
         assert!(velocity < 0x80);
 
-        // If note type is Rest, velocity must be 0
-        let velocity = match note.note_type {
-            crate::midi_note::MIDINoteType::Rest => 0u8,
-            _ => velocity,
+        // If note type is Rest, velocity must be 0 (and note must be a valid data byte)
+        let (note_number, velocity) = match note.note_type {
+            crate::midi_note::MIDINoteType::Rest => (Some(0u8), 0u8),
+            _ => (note.convert(), velocity),
         };
+        // 0 <= note < 0x80 (128)
+        assert!(note_number.is_some());
 
         let event_status = match status {
             MIDIStatus::RunningStatus => 0,
@@ -107,7 +112,7 @@ impl MIDIChannelVoiceMessage {
         MIDIChannelVoiceMessage {
             delta_time,
             status: event_status,
-            note: (note.convert() as u8),
+            note: note_number.unwrap(),
             velocity,
         }
     }
@@ -138,7 +143,7 @@ impl MIDIChannelVoiceMessage {
     where
         T: byteorder::WriteBytesExt,
     {
-        target.write_u8(self.delta_time)?;
+        write_vlq(self.delta_time, target)?;
         if self.status != 0 {
             target.write_u8(self.status)?;
         }
@@ -146,6 +151,683 @@ impl MIDIChannelVoiceMessage {
         target.write_u8(self.velocity)?;
         Ok(())
     }
+
+    /// Generate the size of this MIDI channel message in bytes (on disk)
+    pub fn gen_size(&self) -> u32 {
+        let status_size = match self.status {
+            0 => 0,
+            _ => 1,
+        };
+        gen_vlq_size(self.delta_time) + status_size + 2
+    }
+}
+
+/// MIDI channel message
+///
+/// Unlike [MIDIChannelVoiceMessage](struct.MIDIChannelVoiceMessage.html), which is optimized
+/// for writing `NoteOn` and `NoteOff` events, this type represents every channel message with its
+/// proper data layout.  `ProgramChange` and `Aftertouch` messages carry a single data byte,
+/// `PitchWheelChange` messages carry a 14-bit value (where `0x2000` means no pitch change),
+/// and all other messages carry two data bytes.  All data values must be less than `0x80`
+/// and all channels less than `0x10`.  For a detailed description of each message type,
+/// see Appendix 1.1 of the document here:
+/// <https://www.cs.cmu.edu/~music/cmsip/readings/Standard-MIDI-file-format-updated.pdf>.
+///
+/// # Examples
+///
+/// ```rust
+/// // Select Acoustic Guitar (nylon) on channel 0 and set volume to 100
+/// let program_change = libatm::MIDIChannelMessage::ProgramChange { channel: 0, program: 24 };
+/// let volume = libatm::MIDIChannelMessage::ControlChange { channel: 0, controller: 7, value: 100 };
+/// let mut buffer = Vec::new();
+/// program_change.write_buffer(&mut buffer).unwrap();
+/// volume.write_buffer(&mut buffer).unwrap();
+/// assert_eq!(vec![0xc0, 24, 0xb0, 7, 100], buffer);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MIDIChannelMessage {
+    /// Note released
+    NoteOff { channel: u8, note: u8, velocity: u8 },
+    /// Note pressed (a velocity of 0 is equivalent to `NoteOff`)
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    /// Pressure on key after pressed down
+    PolyphonicAftertouch { channel: u8, note: u8, pressure: u8 },
+    /// Controller value change
+    ControlChange { channel: u8, controller: u8, value: u8 },
+    /// Change program (patch) number
+    ProgramChange { channel: u8, program: u8 },
+    /// Greatest pressure on any key after pressed down
+    Aftertouch { channel: u8, pressure: u8 },
+    /// Change pitch wheel (14-bit value, `0x2000` is centered)
+    PitchWheelChange { channel: u8, value: u16 },
+}
+
+impl MIDIChannelMessage {
+    /// Create `MIDIChannelMessage` from status byte and data bytes
+    ///
+    /// Returns `None` if the status byte is not a channel message status byte (between
+    /// `0x80` and `0xEF`).  If the message type carries two data bytes and `data2` is `None`, `0` is used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let message = libatm::MIDIChannelMessage::from_data(0xc1, 24, None);
+    /// assert_eq!(Some(libatm::MIDIChannelMessage::ProgramChange { channel: 1, program: 24 }), message);
+    /// assert_eq!(None, libatm::MIDIChannelMessage::from_data(0xff, 0x2f, Some(0)));
+    /// ```
+    pub fn from_data(status: u8, data1: u8, data2: Option<u8>) -> Option<Self> {
+        let channel = status & 0x0f;
+        let data2 = data2.unwrap_or(0);
+        match status >> 4 {
+            0b1000 => Some(Self::NoteOff { channel, note: data1, velocity: data2 }),
+            0b1001 => Some(Self::NoteOn { channel, note: data1, velocity: data2 }),
+            0b1010 => Some(Self::PolyphonicAftertouch { channel, note: data1, pressure: data2 }),
+            0b1011 => Some(Self::ControlChange { channel, controller: data1, value: data2 }),
+            0b1100 => Some(Self::ProgramChange { channel, program: data1 }),
+            0b1101 => Some(Self::Aftertouch { channel, pressure: data1 }),
+            0b1110 => Some(Self::PitchWheelChange { channel, value: ((data2 as u16) << 7) | data1 as u16 }),
+            _ => None,
+        }
+    }
+
+    /// [MIDIStatus](enum.MIDIStatus.html) of this message
+    pub fn status(&self) -> MIDIStatus {
+        match self {
+            Self::NoteOff { .. } => MIDIStatus::NoteOff,
+            Self::NoteOn { .. } => MIDIStatus::NoteOn,
+            Self::PolyphonicAftertouch { .. } => MIDIStatus::PolyphonicAftertouch,
+            Self::ControlChange { .. } => MIDIStatus::ControlChange,
+            Self::ProgramChange { .. } => MIDIStatus::ProgramChange,
+            Self::Aftertouch { .. } => MIDIStatus::Aftertouch,
+            Self::PitchWheelChange { .. } => MIDIStatus::PitchWheelChange,
+        }
+    }
+
+    /// Channel of this message
+    pub fn channel(&self) -> u8 {
+        match self {
+            Self::NoteOff { channel, .. }
+            | Self::NoteOn { channel, .. }
+            | Self::PolyphonicAftertouch { channel, .. }
+            | Self::ControlChange { channel, .. }
+            | Self::ProgramChange { channel, .. }
+            | Self::Aftertouch { channel, .. }
+            | Self::PitchWheelChange { channel, .. } => *channel,
+        }
+    }
+
+    /// Status byte (status bits and channel) of this message
+    pub fn status_byte(&self) -> u8 {
+        ((self.status() as u8) << 4) | self.channel()
+    }
+
+    /// Data bytes of this message (second data byte is `None` for single byte messages)
+    pub fn data(&self) -> (u8, Option<u8>) {
+        match *self {
+            Self::NoteOff { note, velocity, .. } => (note, Some(velocity)),
+            Self::NoteOn { note, velocity, .. } => (note, Some(velocity)),
+            Self::PolyphonicAftertouch { note, pressure, .. } => (note, Some(pressure)),
+            Self::ControlChange { controller, value, .. } => (controller, Some(value)),
+            Self::ProgramChange { program, .. } => (program, None),
+            Self::Aftertouch { pressure, .. } => (pressure, None),
+            Self::PitchWheelChange { value, .. } => ((value & 0x7f) as u8, Some((value >> 7) as u8)),
+        }
+    }
+
+    /// Generate the size of this message in bytes (on disk, including status byte)
+    pub fn gen_size(&self) -> u32 {
+        match self.data() {
+            (_, Some(_)) => 3,
+            (_, None) => 2,
+        }
+    }
+
+    /// Write MIDI channel message (including status byte) to buffer
+    ///
+    /// # Notes
+    ///
+    /// Channels of `0x10` or above, data bytes of `0x80` or above, and pitch wheel values
+    /// above `0x3FFF` cannot be represented, and will cause a panic.
+    pub fn write_buffer<T>(&self, target: &mut T) -> std::io::Result<()>
+    where
+        T: byteorder::WriteBytesExt,
+    {
+        // 0 <= channel < 0x10 (16)
+        assert!(self.channel() < 0x10);
+        if let Self::PitchWheelChange { value, .. } = self {
+            // 0 <= value < 0x4000 (14 bits)
+            assert!(*value < 0x4000);
+        }
+        let (data1, data2) = self.data();
+        // 0 <= data < 0x80 (128)
+        assert!(data1 < 0x80 && data2.unwrap_or(0) < 0x80);
+
+        target.write_u8(self.status_byte())?;
+        target.write_u8(data1)?;
+        if let Some(data2) = data2 {
+            target.write_u8(data2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error type for reading MIDI events from a buffer
+#[derive(Debug, thiserror::Error)]
+pub enum ReadMIDIEventError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid variable-length quantity (longer than 4 bytes)")]
+    InvalidVariableLengthQuantity,
+    #[error("Invalid data byte {byte:#04x} (must be less than 0x80)")]
+    InvalidDataByte { byte: u8 },
+    #[error("Found data byte {byte:#04x} without running status")]
+    MissingRunningStatus { byte: u8 },
+    #[error("Unsupported status byte {status:#04x}")]
+    UnsupportedStatus { status: u8 },
+}
+
+/// Largest value that can be represented as a variable-length quantity
+pub const MAX_VLQ: u32 = 0x0fff_ffff;
+
+/// Generate the size of a variable-length quantity in bytes
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(1, libatm::gen_vlq_size(0x7f));
+/// assert_eq!(2, libatm::gen_vlq_size(0x80));
+/// ```
+pub fn gen_vlq_size(value: u32) -> u32 {
+    match value {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        0x4000..=0x001f_ffff => 3,
+        _ => 4,
+    }
+}
+
+/// Write variable-length quantity to buffer
+///
+/// See [read_vlq](fn.read_vlq.html) for a description of the encoding.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut buffer = Vec::new();
+/// libatm::write_vlq(0x80, &mut buffer).unwrap();
+/// assert_eq!(vec![0x81, 0x00], buffer);
+/// ```
+///
+/// # Notes
+///
+/// Values larger than [MAX_VLQ](constant.MAX_VLQ.html) cannot be represented, and will cause a panic.
+pub fn write_vlq<T>(value: u32, target: &mut T) -> std::io::Result<()>
+where
+    T: byteorder::WriteBytesExt,
+{
+    assert!(value <= MAX_VLQ);
+    let size = gen_vlq_size(value);
+    for idx in (1..size).rev() {
+        target.write_u8((((value >> (7 * idx)) & 0x7f) as u8) | 0x80)?;
+    }
+    target.write_u8((value & 0x7f) as u8)?;
+    Ok(())
+}
+
+/// Read variable-length quantity from buffer
+///
+/// Delta times and the lengths of meta and system exclusive events are stored as
+/// variable-length quantities: 7 bits per byte, most significant bits first, with
+/// the high bit of every byte except the last set.  The MIDI spec limits these to
+/// 4 bytes (`0x0FFFFFFF`).  For more information, see section 1.1 of the document here:
+/// <https://www.cs.cmu.edu/~music/cmsip/readings/Standard-MIDI-file-format-updated.pdf>.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut buffer: &[u8] = &[0x81, 0x00];
+/// assert_eq!(0x80, libatm::read_vlq(&mut buffer).unwrap());
+/// ```
+pub fn read_vlq<T>(source: &mut T) -> Result<u32, ReadMIDIEventError>
+where
+    T: byteorder::ReadBytesExt,
+{
+    let mut value = 0u32;
+    for _ in 0..4 {
+        let byte = source.read_u8()?;
+        value = (value << 7) | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(ReadMIDIEventError::InvalidVariableLengthQuantity)
+}
+
+/// MIDI meta event
+///
+/// Meta events carry information that is not sent to MIDI devices, such as the
+/// tempo of the track or the end of the track chunk.  Each meta event is stored as
+/// `FF <type> <length> <data>`, where length is a variable-length quantity.  For a detailed
+/// description of each meta event type, see section 3 of the document here:
+/// <https://www.cs.cmu.edu/~music/cmsip/readings/Standard-MIDI-file-format-updated.pdf>.
+///
+/// # Examples
+///
+/// ```rust
+/// // Set tempo to 90 BPM and time signature to 3/4
+/// let tempo = libatm::MIDIMetaEvent::from_bpm(90);
+/// assert_eq!(libatm::MIDIMetaEvent::Tempo(666_666), tempo);
+/// let time_signature = libatm::MIDIMetaEvent::TimeSignature {
+///     numerator: 3,
+///     denominator: 2,
+///     clocks_per_click: 24,
+///     notated_32nd_notes: 8,
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum MIDIMetaEvent {
+    /// Sequence number (`FF 00 02`)
+    SequenceNumber(u16),
+    /// Any amount of text describing anything (`FF 01`)
+    Text(String),
+    /// Copyright notice (`FF 02`)
+    Copyright(String),
+    /// Name of the sequence (in the first track) or track (`FF 03`)
+    TrackName(String),
+    /// Description of the instrumentation used in the track (`FF 04`)
+    InstrumentName(String),
+    /// Lyric to be sung, generally one syllable per event (`FF 05`)
+    Lyric(String),
+    /// Name of a point in the sequence, such as a rehearsal letter (`FF 06`)
+    Marker(String),
+    /// Description of something happening at a point in the score (`FF 07`)
+    CuePoint(String),
+    /// Name of the program (patch) used in the track (`FF 08`)
+    ProgramName(String),
+    /// Name of the device the track is intended for (`FF 09`)
+    DeviceName(String),
+    /// MIDI channel to associate with subsequent meta and SysEx events (`FF 20 01`)
+    ChannelPrefix(u8),
+    /// MIDI port the track is intended for (`FF 21 01`)
+    Port(u8),
+    /// End of track (`FF 2F 00`), required as the last event of every track chunk
+    EndOfTrack,
+    /// Tempo in microseconds per quarter-note, at most `0xFFFFFF` (`FF 51 03`)
+    Tempo(u32),
+    /// SMPTE time at which the track should start (`FF 54 05`)
+    SMPTEOffset {
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        frames: u8,
+        fractional_frames: u8,
+    },
+    /// Time signature (`FF 58 04`), where `denominator` is a (negative) power
+    /// of two (`2` represents a quarter-note, `3` an eighth-note, and so on)
+    TimeSignature {
+        numerator: u8,
+        denominator: u8,
+        clocks_per_click: u8,
+        notated_32nd_notes: u8,
+    },
+    /// Key signature (`FF 59 02`), where `sharps_flats` is the number of sharps (positive)
+    /// or flats (negative) between -7 and 7
+    KeySignature { sharps_flats: i8, minor: bool },
+    /// Sequencer-specific data (`FF 7F`)
+    SequencerSpecific(Vec<u8>),
+    /// Meta event type not modeled by this library (or with malformed data), passed through unchanged
+    Unknown { meta_type: u8, data: Vec<u8> },
+}
+
+impl MIDIMetaEvent {
+    /// Create `Tempo` meta event from beats (quarter-notes) per minute
+    ///
+    /// # Notes
+    ///
+    /// Tempos slower than the slowest tempo that can be represented (`0xFFFFFF` microseconds
+    /// per quarter-note, about 3.6 BPM) are clamped to it.  Panics if `bpm` is 0.
+    pub fn from_bpm(bpm: u32) -> Self {
+        assert!(bpm > 0);
+        Self::Tempo((60_000_000 / bpm).min(0x00ff_ffff))
+    }
+
+    /// Create `MIDIMetaEvent` from its type byte and data
+    ///
+    /// Meta events with an unknown type, or whose data does not match the expected
+    /// length (or encoding) for its type, are returned as `Unknown`.
+    pub fn from_data(meta_type: u8, data: Vec<u8>) -> Self {
+        let event = match (meta_type, data.as_slice()) {
+            (0x00, &[msb, lsb]) => Some(Self::SequenceNumber(((msb as u16) << 8) | lsb as u16)),
+            (0x01..=0x09, _) => std::str::from_utf8(&data)
+                .ok()
+                .map(|text| text.to_string())
+                .map(|text| match meta_type {
+                    0x01 => Self::Text(text),
+                    0x02 => Self::Copyright(text),
+                    0x03 => Self::TrackName(text),
+                    0x04 => Self::InstrumentName(text),
+                    0x05 => Self::Lyric(text),
+                    0x06 => Self::Marker(text),
+                    0x07 => Self::CuePoint(text),
+                    0x08 => Self::ProgramName(text),
+                    _ => Self::DeviceName(text),
+                }),
+            (0x20, &[channel]) => Some(Self::ChannelPrefix(channel)),
+            (0x21, &[port]) => Some(Self::Port(port)),
+            (0x2f, &[]) => Some(Self::EndOfTrack),
+            (0x51, &[b0, b1, b2]) => Some(Self::Tempo(((b0 as u32) << 16) | ((b1 as u32) << 8) | b2 as u32)),
+            (0x54, &[hours, minutes, seconds, frames, fractional_frames]) => Some(Self::SMPTEOffset {
+                hours,
+                minutes,
+                seconds,
+                frames,
+                fractional_frames,
+            }),
+            (0x58, &[numerator, denominator, clocks_per_click, notated_32nd_notes]) => Some(Self::TimeSignature {
+                numerator,
+                denominator,
+                clocks_per_click,
+                notated_32nd_notes,
+            }),
+            (0x59, &[sharps_flats, minor]) if minor < 2 => Some(Self::KeySignature {
+                sharps_flats: sharps_flats as i8,
+                minor: minor == 1,
+            }),
+            (0x7f, _) => Some(Self::SequencerSpecific(data.clone())),
+            _ => None,
+        };
+        event.unwrap_or(Self::Unknown { meta_type, data })
+    }
+
+    /// Type byte of this meta event
+    pub fn meta_type(&self) -> u8 {
+        match self {
+            Self::SequenceNumber(_) => 0x00,
+            Self::Text(_) => 0x01,
+            Self::Copyright(_) => 0x02,
+            Self::TrackName(_) => 0x03,
+            Self::InstrumentName(_) => 0x04,
+            Self::Lyric(_) => 0x05,
+            Self::Marker(_) => 0x06,
+            Self::CuePoint(_) => 0x07,
+            Self::ProgramName(_) => 0x08,
+            Self::DeviceName(_) => 0x09,
+            Self::ChannelPrefix(_) => 0x20,
+            Self::Port(_) => 0x21,
+            Self::EndOfTrack => 0x2f,
+            Self::Tempo(_) => 0x51,
+            Self::SMPTEOffset { .. } => 0x54,
+            Self::TimeSignature { .. } => 0x58,
+            Self::KeySignature { .. } => 0x59,
+            Self::SequencerSpecific(_) => 0x7f,
+            Self::Unknown { meta_type, .. } => *meta_type,
+        }
+    }
+
+    /// Generate data bytes of this meta event (excluding type and length)
+    ///
+    /// # Notes
+    ///
+    /// `Tempo` values larger than `0xFFFFFF` cannot be represented, and will cause a panic.
+    pub fn gen_data(&self) -> Vec<u8> {
+        match self {
+            Self::SequenceNumber(number) => number.to_be_bytes().to_vec(),
+            Self::Text(text)
+            | Self::Copyright(text)
+            | Self::TrackName(text)
+            | Self::InstrumentName(text)
+            | Self::Lyric(text)
+            | Self::Marker(text)
+            | Self::CuePoint(text)
+            | Self::ProgramName(text)
+            | Self::DeviceName(text) => text.as_bytes().to_vec(),
+            Self::ChannelPrefix(channel) => vec![*channel],
+            Self::Port(port) => vec![*port],
+            Self::EndOfTrack => Vec::new(),
+            Self::Tempo(tempo) => {
+                // 0 <= tempo <= 0xFFFFFF (24 bits)
+                assert!(*tempo <= 0x00ff_ffff);
+                tempo.to_be_bytes()[1..].to_vec()
+            },
+            Self::SMPTEOffset { hours, minutes, seconds, frames, fractional_frames } => {
+                vec![*hours, *minutes, *seconds, *frames, *fractional_frames]
+            },
+            Self::TimeSignature { numerator, denominator, clocks_per_click, notated_32nd_notes } => {
+                vec![*numerator, *denominator, *clocks_per_click, *notated_32nd_notes]
+            },
+            Self::KeySignature { sharps_flats, minor } => vec![*sharps_flats as u8, *minor as u8],
+            Self::SequencerSpecific(data) => data.clone(),
+            Self::Unknown { data, .. } => data.clone(),
+        }
+    }
+
+    /// Generate the size of this meta event in bytes (on disk, excluding delta time)
+    pub fn gen_size(&self) -> u32 {
+        // Length of the data bytes (see: gen_data)
+        let length = match self {
+            Self::SequenceNumber(_) => 2,
+            Self::Text(text)
+            | Self::Copyright(text)
+            | Self::TrackName(text)
+            | Self::InstrumentName(text)
+            | Self::Lyric(text)
+            | Self::Marker(text)
+            | Self::CuePoint(text)
+            | Self::ProgramName(text)
+            | Self::DeviceName(text) => text.len() as u32,
+            Self::ChannelPrefix(_) | Self::Port(_) => 1,
+            Self::EndOfTrack => 0,
+            Self::Tempo(_) => 3,
+            Self::SMPTEOffset { .. } => 5,
+            Self::TimeSignature { .. } => 4,
+            Self::KeySignature { .. } => 2,
+            Self::SequencerSpecific(data) | Self::Unknown { data, .. } => data.len() as u32,
+        };
+        2 + gen_vlq_size(length) + length
+    }
+
+    /// Write meta event (including the `0xFF` status byte) to buffer
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = Vec::new();
+    /// libatm::MIDIMetaEvent::EndOfTrack.write_buffer(&mut buffer).unwrap();
+    /// assert_eq!(vec![0xff, 0x2f, 0x00], buffer);
+    /// ```
+    pub fn write_buffer<T>(&self, target: &mut T) -> std::io::Result<()>
+    where
+        T: byteorder::WriteBytesExt,
+    {
+        let data = self.gen_data();
+        target.write_u8(0xff)?;
+        target.write_u8(self.meta_type())?;
+        write_vlq(data.len() as u32, target)?;
+        target.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Read meta event (following the `0xFF` status byte) from buffer
+    pub fn read_buffer<T>(source: &mut T) -> Result<Self, ReadMIDIEventError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let meta_type = source.read_u8()?;
+        let data = read_event_data(source)?;
+        Ok(Self::from_data(meta_type, data))
+    }
+}
+
+/// Event in a MIDI track chunk
+///
+/// Track chunks may contain three kinds of events: channel messages, meta events,
+/// and system exclusive (SysEx) events.  Channel messages are stored with their full status
+/// (including the channel), regardless of whether running status was used on disk.
+/// For a detailed description of each event type, see section 2.3 of the document here:
+/// <https://www.cs.cmu.edu/~music/cmsip/readings/Standard-MIDI-file-format-updated.pdf>.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MIDIEvent {
+    /// Channel message
+    Channel(MIDIChannelMessage),
+    /// Meta event
+    Meta(MIDIMetaEvent),
+    /// System exclusive event (`0xF0`)
+    SysEx { data: Vec<u8> },
+    /// Escaped system exclusive event (`0xF7`)
+    Escape { data: Vec<u8> },
+}
+
+/// MIDI track event
+///
+/// Combines a [MIDIEvent](enum.MIDIEvent.html) with the time delta
+/// (in ticks) since the previous event in the track.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MIDITrackEvent {
+    pub delta_time: u32,
+    pub event: MIDIEvent,
+}
+
+impl MIDITrackEvent {
+    /// Read MIDI track event from buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: buffer to read from
+    /// * `running_status`: status byte of the previous channel message, updated as
+    ///   events are read (should be `None` at the start of each track)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// // NoteOn C4, followed by a NoteOn C4 with velocity 0 (running status) after 1 tick
+    /// let mut buffer: &[u8] = &[0x00, 0x90, 0x3c, 0x64, 0x01, 0x3c, 0x00];
+    /// let mut running_status = None;
+    /// let _note_on = libatm::MIDITrackEvent::read_buffer(&mut buffer, &mut running_status).unwrap();
+    /// let note_off = libatm::MIDITrackEvent::read_buffer(&mut buffer, &mut running_status).unwrap();
+    /// assert_eq!(
+    ///     libatm::MIDIEvent::Channel(libatm::MIDIChannelMessage::NoteOn { channel: 0, note: 0x3c, velocity: 0 }),
+    ///     note_off.event,
+    /// );
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// Meta and system exclusive events cancel running status, as required by the MIDI spec.
+    pub fn read_buffer<T>(
+        source: &mut T,
+        running_status: &mut Option<u8>,
+    ) -> Result<Self, ReadMIDIEventError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let delta_time = read_vlq(source)?;
+        let byte = source.read_u8()?;
+        let event = match byte {
+            0x00..=0x7f => {
+                let status = running_status.ok_or(ReadMIDIEventError::MissingRunningStatus { byte })?;
+                read_channel_event(source, status, byte)?
+            },
+            0x80..=0xef => {
+                *running_status = Some(byte);
+                let data1 = source.read_u8()?;
+                read_channel_event(source, byte, data1)?
+            },
+            0xf0 | 0xf7 => {
+                *running_status = None;
+                let data = read_event_data(source)?;
+                match byte {
+                    0xf0 => MIDIEvent::SysEx { data },
+                    _ => MIDIEvent::Escape { data },
+                }
+            },
+            0xff => {
+                *running_status = None;
+                MIDIEvent::Meta(MIDIMetaEvent::read_buffer(source)?)
+            },
+            _ => return Err(ReadMIDIEventError::UnsupportedStatus { status: byte }),
+        };
+        Ok(Self { delta_time, event })
+    }
+
+    /// Whether this event is the End of Track meta event (`FF 2F 00`)
+    pub fn is_end_of_track(&self) -> bool {
+        matches!(self.event, MIDIEvent::Meta(MIDIMetaEvent::EndOfTrack))
+    }
+
+    /// Write MIDI track event to buffer
+    ///
+    /// Channel messages are always written with their status byte (running status is not used).
+    pub fn write_buffer<T>(&self, target: &mut T) -> std::io::Result<()>
+    where
+        T: byteorder::WriteBytesExt,
+    {
+        write_vlq(self.delta_time, target)?;
+        match &self.event {
+            MIDIEvent::Channel(message) => message.write_buffer(target)?,
+            MIDIEvent::Meta(meta_event) => meta_event.write_buffer(target)?,
+            MIDIEvent::SysEx { data } | MIDIEvent::Escape { data } => {
+                let status = match self.event {
+                    MIDIEvent::SysEx { .. } => 0xf0,
+                    _ => 0xf7,
+                };
+                target.write_u8(status)?;
+                write_vlq(data.len() as u32, target)?;
+                target.write_all(data)?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Generate the size of this MIDI track event in bytes (on disk)
+    pub fn gen_size(&self) -> u32 {
+        let event_size = match &self.event {
+            MIDIEvent::Channel(message) => message.gen_size(),
+            MIDIEvent::Meta(meta_event) => meta_event.gen_size(),
+            MIDIEvent::SysEx { data } | MIDIEvent::Escape { data } => {
+                1 + gen_vlq_size(data.len() as u32) + data.len() as u32
+            },
+        };
+        gen_vlq_size(self.delta_time) + event_size
+    }
+}
+
+/// Read data bytes of a channel message given its status and first data byte
+fn read_channel_event<T>(source: &mut T, status: u8, data1: u8) -> Result<MIDIEvent, ReadMIDIEventError>
+where
+    T: byteorder::ReadBytesExt,
+{
+    if data1 >= 0x80 {
+        return Err(ReadMIDIEventError::InvalidDataByte { byte: data1 });
+    }
+    // ProgramChange and Aftertouch messages carry a single data byte
+    let data2 = match status >> 4 {
+        0b1100 | 0b1101 => None,
+        _ => {
+            let data2 = source.read_u8()?;
+            if data2 >= 0x80 {
+                return Err(ReadMIDIEventError::InvalidDataByte { byte: data2 });
+            }
+            Some(data2)
+        },
+    };
+    let message = MIDIChannelMessage::from_data(status, data1, data2)
+        .ok_or(ReadMIDIEventError::UnsupportedStatus { status })?;
+    Ok(MIDIEvent::Channel(message))
+}
+
+/// Read length-prefixed data of a meta or system exclusive event
+fn read_event_data<T>(source: &mut T) -> Result<Vec<u8>, ReadMIDIEventError>
+where
+    T: byteorder::ReadBytesExt,
+{
+    let length = read_vlq(source)?;
+    // Only allocate as much data as is actually present in the buffer
+    let mut data = Vec::new();
+    std::io::Read::read_to_end(&mut std::io::Read::take(source, length as u64), &mut data)?;
+    if data.len() < length as usize {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(data)
 }
 
 #[cfg(test)]
@@ -173,5 +855,205 @@ mod tests {
         let note = crate::midi_note::MIDINote::new(crate::midi_note::MIDINoteType::Rest, 4);
         let event = MIDIChannelVoiceMessage::new(0, &note, 0x64, MIDIStatus::NoteOn, 0);
         assert_eq!(0, event.velocity);
+        assert_eq!(0, event.note);
+    }
+
+    #[test]
+    fn test_read_vlq() {
+        let cases: Vec<(&[u8], u32)> = vec![
+            (&[0x00], 0x00),
+            (&[0x7f], 0x7f),
+            (&[0x81, 0x00], 0x80),
+            (&[0xff, 0x7f], 0x3fff),
+            (&[0x81, 0x80, 0x00], 0x4000),
+            (&[0xff, 0xff, 0xff, 0x7f], 0x0fffffff),
+        ];
+        for (mut input, expected) in cases.into_iter() {
+            assert_eq!(expected, read_vlq(&mut input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_write_vlq_round_trip() {
+        for value in [0, 0x40, 0x7f, 0x80, 0x2000, 0x3fff, 0x4000, 0x001f_ffff, 0x0020_0000, MAX_VLQ].iter() {
+            let mut buffer = Vec::new();
+            write_vlq(*value, &mut buffer).unwrap();
+            assert_eq!(gen_vlq_size(*value) as usize, buffer.len());
+            assert_eq!(*value, read_vlq(&mut buffer.as_slice()).unwrap());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_write_vlq_too_large() {
+        let _ = write_vlq(MAX_VLQ + 1, &mut Vec::new());
+    }
+
+    #[test]
+    fn test_midi_channel_message_multi_byte_delta_time() {
+        let note = crate::midi_note::MIDINote::new(crate::midi_note::MIDINoteType::C, 4);
+        let event = MIDIChannelVoiceMessage::new(200, &note, 0, MIDIStatus::RunningStatus, 0);
+        let mut buffer = Vec::new();
+        event.write_buffer(&mut buffer).unwrap();
+        assert_eq!(vec![0x81, 0x48, 0x3c, 0x00], buffer);
+        assert_eq!(4, event.gen_size());
+    }
+
+    #[test]
+    fn test_read_vlq_too_long() {
+        let mut input: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x00];
+        assert!(matches!(
+            read_vlq(&mut input),
+            Err(ReadMIDIEventError::InvalidVariableLengthQuantity)
+        ));
+    }
+
+    #[test]
+    fn test_read_track_event_meta_cancels_running_status() {
+        let mut input: &[u8] = &[0x00, 0x90, 0x3c, 0x64, 0x00, 0xff, 0x2f, 0x00, 0x00, 0x3c, 0x00];
+        let mut running_status = None;
+        let _ = MIDITrackEvent::read_buffer(&mut input, &mut running_status).unwrap();
+        let end_of_track = MIDITrackEvent::read_buffer(&mut input, &mut running_status).unwrap();
+        assert!(end_of_track.is_end_of_track());
+        assert!(matches!(
+            MIDITrackEvent::read_buffer(&mut input, &mut running_status),
+            Err(ReadMIDIEventError::MissingRunningStatus { byte: 0x3c })
+        ));
+    }
+
+    #[test]
+    fn test_meta_event_round_trip() {
+        let events = vec![
+            MIDIMetaEvent::SequenceNumber(0x0102),
+            MIDIMetaEvent::Text("text".to_string()),
+            MIDIMetaEvent::Copyright("(c) 2020 All The Music, LLC".to_string()),
+            MIDIMetaEvent::TrackName("Melody".to_string()),
+            MIDIMetaEvent::InstrumentName("Piano".to_string()),
+            MIDIMetaEvent::Lyric("la".to_string()),
+            MIDIMetaEvent::Marker("A".to_string()),
+            MIDIMetaEvent::CuePoint("Fade".to_string()),
+            MIDIMetaEvent::ProgramName("Grand".to_string()),
+            MIDIMetaEvent::DeviceName("Synth".to_string()),
+            MIDIMetaEvent::ChannelPrefix(0x0f),
+            MIDIMetaEvent::Port(0x01),
+            MIDIMetaEvent::EndOfTrack,
+            MIDIMetaEvent::Tempo(500_000),
+            MIDIMetaEvent::SMPTEOffset { hours: 1, minutes: 2, seconds: 3, frames: 4, fractional_frames: 5 },
+            MIDIMetaEvent::TimeSignature { numerator: 6, denominator: 3, clocks_per_click: 36, notated_32nd_notes: 8 },
+            MIDIMetaEvent::KeySignature { sharps_flats: -3, minor: true },
+            MIDIMetaEvent::SequencerSpecific(vec![0x00, 0x00, 0x41]),
+            MIDIMetaEvent::Unknown { meta_type: 0x60, data: vec![0x01] },
+        ];
+        for event in events.into_iter() {
+            let mut buffer = Vec::new();
+            event.write_buffer(&mut buffer).unwrap();
+            assert_eq!(event.gen_size() as usize, buffer.len());
+            assert_eq!(event, MIDIMetaEvent::read_buffer(&mut &buffer[1..]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_meta_event_malformed_data_is_unknown() {
+        let inputs = vec![
+            (0x00, vec![0x01]),
+            (0x03, vec![0xff, 0xfe]),
+            (0x2f, vec![0x00]),
+            (0x51, vec![0x07, 0xa1]),
+            (0x59, vec![0x02, 0x02]),
+        ];
+        for (meta_type, data) in inputs.into_iter() {
+            let expected = MIDIMetaEvent::Unknown { meta_type, data: data.clone() };
+            assert_eq!(expected, MIDIMetaEvent::from_data(meta_type, data));
+        }
+    }
+
+    #[test]
+    fn test_meta_event_tempo_from_bpm() {
+        assert_eq!(MIDIMetaEvent::Tempo(500_000), MIDIMetaEvent::from_bpm(120));
+        assert_eq!(MIDIMetaEvent::Tempo(15_000_000), MIDIMetaEvent::from_bpm(4));
+        // Slower tempos are clamped to the slowest tempo that can be written
+        for bpm in [1, 3].iter() {
+            let tempo = MIDIMetaEvent::from_bpm(*bpm);
+            assert_eq!(MIDIMetaEvent::Tempo(0x00ff_ffff), tempo);
+            let mut buffer = Vec::new();
+            tempo.write_buffer(&mut buffer).unwrap();
+            assert_eq!(tempo.gen_size() as usize, buffer.len());
+        }
+    }
+
+    #[test]
+    fn test_track_event_round_trip() {
+        let events = vec![
+            MIDITrackEvent { delta_time: 0, event: MIDIEvent::Channel(MIDIChannelMessage::NoteOn { channel: 1, note: 0x3c, velocity: 0x64 }) },
+            MIDITrackEvent { delta_time: 0x80, event: MIDIEvent::Channel(MIDIChannelMessage::ProgramChange { channel: 1, program: 0x05 }) },
+            MIDITrackEvent { delta_time: 0, event: MIDIEvent::Meta(MIDIMetaEvent::Unknown { meta_type: 0x7e, data: vec![1, 2, 3] }) },
+            MIDITrackEvent { delta_time: 0, event: MIDIEvent::SysEx { data: vec![0x7e, 0xf7] } },
+            MIDITrackEvent { delta_time: 3, event: MIDIEvent::Meta(MIDIMetaEvent::EndOfTrack) },
+        ];
+        for event in events.into_iter() {
+            let mut buffer = Vec::new();
+            event.write_buffer(&mut buffer).unwrap();
+            assert_eq!(event.gen_size() as usize, buffer.len());
+            assert_eq!(event, MIDITrackEvent::read_buffer(&mut buffer.as_slice(), &mut None).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_read_track_event_truncated_data() {
+        // SysEx event claiming 0x0FFFFFFF bytes of data, followed by only 2 bytes
+        let mut input: &[u8] = &[0x00, 0xf0, 0xff, 0xff, 0xff, 0x7f, 0x01, 0x02];
+        assert!(matches!(
+            MIDITrackEvent::read_buffer(&mut input, &mut None),
+            Err(ReadMIDIEventError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_track_event_program_change() {
+        let mut input: &[u8] = &[0x00, 0xc0, 0x05];
+        let event = MIDITrackEvent::read_buffer(&mut input, &mut None).unwrap();
+        assert_eq!(MIDIEvent::Channel(MIDIChannelMessage::ProgramChange { channel: 0, program: 0x05 }), event.event);
+    }
+
+    #[test]
+    fn test_channel_message_round_trip() {
+        let messages = vec![
+            MIDIChannelMessage::NoteOff { channel: 0, note: 0x3c, velocity: 0x40 },
+            MIDIChannelMessage::NoteOn { channel: 1, note: 0x3c, velocity: 0x64 },
+            MIDIChannelMessage::PolyphonicAftertouch { channel: 2, note: 0x3c, pressure: 0x10 },
+            MIDIChannelMessage::ControlChange { channel: 3, controller: 0x0b, value: 0x7f },
+            MIDIChannelMessage::ProgramChange { channel: 4, program: 0x18 },
+            MIDIChannelMessage::Aftertouch { channel: 5, pressure: 0x20 },
+            MIDIChannelMessage::PitchWheelChange { channel: 15, value: 0x3fff },
+        ];
+        for message in messages.into_iter() {
+            let mut buffer = vec![0x00];
+            message.write_buffer(&mut buffer).unwrap();
+            assert_eq!(message.gen_size() as usize + 1, buffer.len());
+            let event = MIDITrackEvent::read_buffer(&mut buffer.as_slice(), &mut None).unwrap();
+            assert_eq!(MIDIEvent::Channel(message), event.event);
+        }
+    }
+
+    #[test]
+    fn test_channel_message_pitch_wheel_data() {
+        let message = MIDIChannelMessage::PitchWheelChange { channel: 0, value: 0x2000 };
+        let mut buffer = Vec::new();
+        message.write_buffer(&mut buffer).unwrap();
+        assert_eq!(vec![0xe0, 0x00, 0x40], buffer);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_channel_message_invalid_pitch_wheel() {
+        let message = MIDIChannelMessage::PitchWheelChange { channel: 0, value: 0x4000 };
+        let _ = message.write_buffer(&mut Vec::new());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_channel_message_invalid_data() {
+        let message = MIDIChannelMessage::ControlChange { channel: 0, controller: 0x80, value: 0 };
+        let _ = message.write_buffer(&mut Vec::new());
     }
 }
@@ -6,7 +6,119 @@
 // To view a copy of this license, visit http://creativecommons.org/licenses/by/4.0/ or send
 // a letter to Creative Commons, PO Box 1866, Mountain View, CA 94042, USA.
 
-use crate::midi_event::{MIDIChannelVoiceMessage, MIDIStatus};
+use crate::midi_event::{
+    MIDIChannelMessage,
+    MIDIChannelVoiceMessage,
+    MIDIEvent,
+    MIDIMetaEvent,
+    MIDIStatus,
+    MIDITrackEvent,
+    ReadMIDIEventError,
+};
+
+/// Error type for decoding a [MIDIFile](struct.MIDIFile.html) hash
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ParseMIDIHashError {
+    #[error("Invalid note in hash (expected MIDI note number or 'r', found {input})")]
+    InvalidNote { input: String },
+    #[error("Invalid rhythm in hash (expected '<start>-<duration>', found {input})")]
+    InvalidRhythm { input: String },
+    #[error("Invalid rhythm length in hash (expected one '<start>-<duration>' per note ({expected}), found {found})")]
+    InvalidRhythmLength { expected: usize, found: usize },
+}
+
+/// Error type for reading MIDI files from a buffer
+#[derive(Debug, thiserror::Error)]
+pub enum ReadMIDIFileError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid chunk type (expected {expected:?}, found {found:?})")]
+    InvalidChunkType { expected: String, found: String },
+    #[error("Invalid header chunk length (expected at least 6, found {0})")]
+    InvalidHeaderLength(u32),
+    #[error("Invalid MIDI format {0}")]
+    InvalidFormat(u16),
+    #[error("Invalid number of tracks for {format:?} (found {tracks})")]
+    InvalidTrackCount { format: MIDIFormat, tracks: u16 },
+    #[error("Invalid event {event} in track {track}")]
+    InvalidEvent {
+        track: usize,
+        event: usize,
+        #[source]
+        source: ReadMIDIEventError,
+    },
+    #[error("Note number {0} cannot be represented as a MIDINote")]
+    UnrepresentableNote(u8),
+    #[error("Track longer than the maximum number of ticks ({})", u32::MAX)]
+    TickOverflow,
+    #[error("Notes on more than one channel in a track (found channels {first} and {second})")]
+    MixedChannels { first: u8, second: u8 },
+}
+
+/// Error type for MIDI files that cannot be written
+#[derive(Debug, thiserror::Error)]
+pub enum WriteMIDIFileError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Too many tracks (found {tracks}, the header chunk can count at most 65535)")]
+    TooManyTracks { tracks: usize },
+    #[error("Note released after the last tick ({})", u32::MAX)]
+    TickOverflow,
+    #[error("Delta time too large (found {delta_time}, expected at most {})", crate::midi_event::MAX_VLQ)]
+    DeltaTimeOverflow { delta_time: u32 },
+    #[error(transparent)]
+    InvalidNote(#[from] crate::midi_note::MIDINoteRangeError),
+}
+
+/// Read chunk type and length from buffer
+fn read_chunk_header<T>(source: &mut T) -> Result<([u8; 4], u32), ReadMIDIFileError>
+where
+    T: byteorder::ReadBytesExt,
+{
+    let mut chunk_type = [0u8; 4];
+    source.read_exact(&mut chunk_type)?;
+    let length = source.read_u32::<byteorder::BigEndian>()?;
+    Ok((chunk_type, length))
+}
+
+/// Skip number of bytes in buffer
+fn skip_bytes<T>(source: &mut T, length: u64) -> Result<(), ReadMIDIFileError>
+where
+    T: byteorder::ReadBytesExt,
+{
+    let skipped = std::io::copy(&mut std::io::Read::take(source, length), &mut std::io::sink())?;
+    if skipped < length {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(())
+}
+
+/// Read number of bytes from buffer
+///
+/// Unlike allocating a buffer of `length` bytes up front, only allocates as much
+/// data as is actually present in the buffer (so a corrupt length cannot exhaust memory).
+fn read_bytes<T>(source: &mut T, length: u64) -> Result<Vec<u8>, ReadMIDIFileError>
+where
+    T: byteorder::ReadBytesExt,
+{
+    let mut data = Vec::new();
+    let read = std::io::Read::read_to_end(&mut std::io::Read::take(source, length), &mut data)?;
+    if (read as u64) < length {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(data)
+}
+
+/// Ensure chunk type read from buffer matches expected chunk type
+fn validate_chunk_type(expected: &[u8], found: &[u8]) -> Result<(), ReadMIDIFileError> {
+    if expected != found {
+        return Err(ReadMIDIFileError::InvalidChunkType {
+            expected: String::from_utf8_lossy(expected).into_owned(),
+            found: String::from_utf8_lossy(found).into_owned(),
+        });
+    }
+    Ok(())
+}
 
 /// MIDI file format
 ///
@@ -24,6 +136,19 @@ pub enum MIDIFormat {
     Format2,
 }
 
+impl std::convert::TryFrom<u16> for MIDIFormat {
+    type Error = ReadMIDIFileError;
+
+    fn try_from(format: u16) -> Result<Self, Self::Error> {
+        match format {
+            0 => Ok(Self::Format0),
+            1 => Ok(Self::Format1),
+            2 => Ok(Self::Format2),
+            _ => Err(ReadMIDIFileError::InvalidFormat(format)),
+        }
+    }
+}
+
 /// MIDI track chunk header
 ///
 ///  Encapsulates the chunk type ('MTrk') and the length
@@ -53,6 +178,18 @@ impl MIDITrackHeader {
         target.write_u32::<byteorder::BigEndian>(self.length)?;
         Ok(())
     }
+
+    /// Read track chunk header from buffer
+    ///
+    /// Fails if the chunk type is not 'MTrk'.
+    pub fn read_buffer<T>(source: &mut T) -> Result<Self, ReadMIDIFileError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let (chunk_type, length) = read_chunk_header(source)?;
+        validate_chunk_type(b"MTrk", &chunk_type)?;
+        Ok(Self::new(chunk_type.to_vec(), length))
+    }
 }
 
 /// MIDI file header
@@ -103,163 +240,2221 @@ impl MIDIHeader {
         target.write_u16::<byteorder::BigEndian>(self.division)?;
         Ok(())
     }
-}
 
-/// Generate size of a MIDI track chunk in bytes given number of notes
-pub fn gen_midi_track_size(num_notes: u32) -> u32 {
-    (num_notes * 6) + 1
+    /// Read header chunk from buffer
+    ///
+    /// Fails if the chunk type is not 'MThd', the format is unknown, or a
+    /// format 0 file declares more than one track.  Any header data beyond the
+    /// three 16-bit fields defined by the spec is skipped.
+    pub fn read_buffer<T>(source: &mut T) -> Result<Self, ReadMIDIFileError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let (chunk_type, length) = read_chunk_header(source)?;
+        validate_chunk_type(b"MThd", &chunk_type)?;
+        if length < 6 {
+            return Err(ReadMIDIFileError::InvalidHeaderLength(length));
+        }
+        let format = source.read_u16::<byteorder::BigEndian>()?;
+        let format = <MIDIFormat as std::convert::TryFrom<u16>>::try_from(format)?;
+        let tracks = source.read_u16::<byteorder::BigEndian>()?;
+        if format == MIDIFormat::Format0 && tracks != 1 {
+            return Err(ReadMIDIFileError::InvalidTrackCount { format, tracks });
+        }
+        let division = source.read_u16::<byteorder::BigEndian>()?;
+        // Skip header data not defined by the spec
+        skip_bytes(source, (length - 6) as u64)?;
+        Ok(Self::new(chunk_type.to_vec(), length, format, tracks, division))
+    }
 }
 
-/// Generate the size of a MIDI file in bytes given number of notes
-pub fn gen_midi_file_size(num_notes: u32) -> u32 {
-    22 + gen_midi_track_size(num_notes)
+/// MIDI track chunk
+///
+/// Combines a [MIDITrackHeader](struct.MIDITrackHeader.html) with the
+/// [events](../midi_event/struct.MIDITrackEvent.html) contained in the track.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MIDITrackChunk {
+    pub header: MIDITrackHeader,
+    pub events: Vec<MIDITrackEvent>,
 }
 
+impl MIDITrackChunk {
+    /// Read track chunk from buffer
+    ///
+    /// Reads exactly the number of bytes specified in the track chunk header.  Events
+    /// following an End of Track meta event (if present) are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: buffer to read from
+    /// * `track`: index of the track in the file (used for error reporting)
+    pub fn read_buffer<T>(source: &mut T, track: usize) -> Result<Self, ReadMIDIFileError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let header = MIDITrackHeader::read_buffer(source)?;
+        Self::read_events(source, header, track)
+    }
+
+    /// Read events of track chunk with (already read) header from buffer
+    fn read_events<T>(
+        source: &mut T,
+        header: MIDITrackHeader,
+        track: usize,
+    ) -> Result<Self, ReadMIDIFileError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let data = read_bytes(source, header.length as u64)?;
 
-/// MIDI file representation
+        let mut data = data.as_slice();
+        let mut running_status = None;
+        let mut events = Vec::new();
+        while !data.is_empty() {
+            let event = MIDITrackEvent::read_buffer(&mut data, &mut running_status)
+                .map_err(|source| ReadMIDIFileError::InvalidEvent {
+                    track,
+                    event: events.len(),
+                    source,
+                })?;
+            let end_of_track = event.is_end_of_track();
+            events.push(event);
+            if end_of_track {
+                break;
+            }
+        }
+        Ok(Self { header, events })
+    }
+}
+
+/// Chunks of a MIDI file
 ///
-/// MIDI files can be complex, allowing for any number of tracks with
-/// different notes and instruments playing simultaneously.  This library
-/// was created for the express purpose of brute-forcing melodies, and thus
-/// only supports a subset of the official MIDI standard.  More specifically,
-/// this implementation is optimized to create the smallest possible single track MIDI
-/// files.
-#[derive(Clone, Debug)]
-pub struct MIDIFile {
-    /// Sequence of notes to generate the track chunk from
-    pub sequence: crate::midi_note::MIDINoteVec,
-    /// Format specification (should always be [MIDIFormat::0](enum.MIDIFormat.html#variant.Format0))
-    pub format: MIDIFormat,
-    /// Number of tracks in MIDI file (should always be `1`)
-    pub tracks: u16,
-    /// Number of ticks to represent a quarter-note (recommended to use `1`)
-    pub division: u16,
+/// Structured representation of an entire MIDI file as read from disk,
+/// consisting of the [header chunk](struct.MIDIHeader.html) and each
+/// [track chunk](struct.MIDITrackChunk.html) in order.  Unlike [MIDIFile](struct.MIDIFile.html),
+/// this representation is lossless and supports MIDI files not created by this library.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MIDIFileChunks {
+    pub header: MIDIHeader,
+    pub tracks: Vec<MIDITrackChunk>,
 }
 
-impl MIDIFile {
-    /// Create new `MIDIFile`
+impl MIDIFileChunks {
+    /// Read MIDI file chunks from buffer
+    ///
+    /// Reads the header chunk followed by the number of track chunks it specifies.
+    /// Chunks with unknown types are skipped, as required by the MIDI spec.
     ///
     /// # Examples
     ///
     /// ```rust
     /// let mfile = libatm::MIDIFile::new(
-    ///     "C:4,CSharp:8,D:5,DSharp:3".parse::<libatm::MIDINoteVec>().unwrap(),
+    ///     "C:4,D:4,E:4".parse::<libatm::MIDINoteVec>().unwrap(),
     ///     libatm::MIDIFormat::Format0,
     ///     1,
-    ///     1,
     /// );
-    /// assert_eq!("601097451", mfile.gen_hash());
+    /// let buffer = mfile.gen_file().unwrap();
+    /// let chunks = libatm::MIDIFileChunks::read_buffer(&mut buffer.as_slice()).unwrap();
+    /// assert_eq!(1, chunks.tracks.len());
+    /// assert_eq!(7, chunks.tracks[0].events.len());
+    /// assert!(chunks.tracks[0].events[6].is_end_of_track());
     /// ```
-    pub fn new(
-        sequence: crate::midi_note::MIDINoteVec,
-        format: MIDIFormat,
-        tracks: u16,
-        division: u16,
-    ) -> MIDIFile {
-        MIDIFile {
-            sequence,
-            format,
-            tracks,
-            division,
+    pub fn read_buffer<T>(source: &mut T) -> Result<Self, ReadMIDIFileError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        let header = MIDIHeader::read_buffer(source)?;
+        let mut tracks = Vec::with_capacity(header.tracks as usize);
+        while tracks.len() < header.tracks as usize {
+            let (chunk_type, length) = read_chunk_header(source)?;
+            if &chunk_type == b"MTrk" {
+                let track_header = MIDITrackHeader::new(chunk_type.to_vec(), length);
+                tracks.push(MIDITrackChunk::read_events(source, track_header, tracks.len())?);
+            } else {
+                skip_bytes(source, length as u64)?;
+            }
         }
+        Ok(Self { header, tracks })
     }
 
-    /// Generate unique hash for this file's `MIDINote` sequence
+    /// Read MIDI file chunks from byte slice
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReadMIDIFileError> {
+        let mut source = bytes;
+        Self::read_buffer(&mut source)
+    }
+
+    /// Read MIDI file chunks from path on disk
+    pub fn read_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ReadMIDIFileError> {
+        let source_file = std::fs::File::open(path)?;
+        let mut source_file = std::io::BufReader::new(source_file);
+        Self::read_buffer(&mut source_file)
+    }
+}
+
+/// Generate size of a MIDI track chunk in bytes given sequence of notes and division
+///
+/// Each note (or rest) is played for `division` ticks (see: [MIDITrack::new](struct.MIDITrack.html#method.new)),
+/// and delta times are encoded as variable-length quantities (see: [gen_vlq_size](../midi_event/fn.gen_vlq_size.html)).
+/// Rests are encoded in the delta time of the next note, or of the End of Track meta event
+/// (at least 4 bytes) if the sequence ends with rests.
+pub fn gen_midi_track_size(sequence: &[crate::midi_note::MIDINote], division: u16) -> u32 {
+    let trailing_rests = sequence
+        .iter()
+        .rev()
+        .take_while(|note| note.note_type == crate::midi_note::MIDINoteType::Rest)
+        .count() as u32;
+    let end_of_track_delta = trailing_rests.saturating_mul(division as u32);
+    gen_minimal_midi_track_size(sequence, division) + 3 + crate::midi_event::gen_vlq_size(end_of_track_delta)
+}
+
+/// Generate the size of a MIDI file in bytes given sequence of notes and division
+pub fn gen_midi_file_size(sequence: &[crate::midi_note::MIDINote], division: u16) -> u32 {
+    22 + gen_midi_track_size(sequence, division)
+}
+
+/// Generate size of a minimal MIDI track chunk (without End of Track) in bytes
+pub fn gen_minimal_midi_track_size(sequence: &[crate::midi_note::MIDINote], division: u16) -> u32 {
+    let division = division as u32;
+    let mut rests = 0u32;
+    let mut size = 0;
+    for note in sequence.iter() {
+        match note.note_type {
+            crate::midi_note::MIDINoteType::Rest => rests = rests.saturating_add(1),
+            _ => {
+                // Preceding rests are encoded in the delta time of the NoteOn event
+                let delta_time = rests.saturating_mul(division);
+                size += crate::midi_event::gen_vlq_size(delta_time) + crate::midi_event::gen_vlq_size(division) + 4;
+                rests = 0;
+            },
+        }
+    }
+    match size {
+        0 => 0,
+        // First NoteOn event includes status byte
+        _ => size + 1,
+    }
+}
+
+/// Generate the size of a minimal MIDI file (without End of Track) in bytes
+pub fn gen_minimal_midi_file_size(sequence: &[crate::midi_note::MIDINote], division: u16) -> u32 {
+    22 + gen_minimal_midi_track_size(sequence, division)
+}
+
+
+/// Generate start tick of the note at `idx` in a sequence of notes played for `division` ticks each
+///
+/// Saturates at the last tick (`u32::MAX`), so sequences too long to be
+/// written fail when the track is written rather than wrapping around.
+fn gen_start(idx: usize, division: u32) -> u32 {
+    <u32 as std::convert::TryFrom<usize>>::try_from(idx)
+        .unwrap_or(u32::MAX)
+        .saturating_mul(division)
+}
+
+/// Maximum number of rests in the hash of a track (see: [MIDIFile::gen_hash](struct.MIDIFile.html#method.gen_hash))
+///
+/// Bounds the length of the hash of tracks with long silences (e.g., read from untrusted files).
+const MAX_HASH_RESTS: u64 = 0xffff;
+
+/// Generate the number of ticks per slot if notes (without rests, ordered by start tick) fill a grid
+/// of slots of equal length until `end`, one note per slot or a rest (see: [MIDIFile::gen_hash](struct.MIDIFile.html#method.gen_hash))
+///
+/// Returns `Some(0)` if every note has a duration of 0 and the track ends at tick 0.
+fn gen_grid_duration(notes: &[&crate::midi_note::MIDITimedNote], end: u64) -> Option<u32> {
+    let duration = notes.first()?.duration;
+    if notes.iter().any(|note| note.duration != duration) {
+        return None;
+    }
+    if duration == 0 {
+        return match end {
+            0 => Some(0),
+            _ => None,
+        };
+    }
+    let slot = duration as u64;
+    // Whether tick is at the boundary of a slot
+    let on_slot = |tick: u64| tick / slot * slot == tick;
+    let on_grid = on_slot(end)
+        && notes.iter().all(|note| on_slot(note.start as u64))
+        && notes.windows(2).all(|pair| pair[0].start < pair[1].start);
+    match on_grid && (end / slot) - notes.len() as u64 <= MAX_HASH_RESTS {
+        true => Some(duration),
+        false => None,
+    }
+}
+
+/// Split the silence of a track read without notes (other than a single rest) into rests of
+/// `division` ticks (see: [MIDIFile::from_chunks](struct.MIDIFile.html#method.from_chunks))
+///
+/// Tracks with silence that is not a multiple of `division`, or longer than the maximum number
+/// of rests in a hash, are left unchanged.
+fn split_rests(track: &mut MIDITrack, division: u16) {
+    let rest = match track.notes.as_slice() {
+        [rest] if rest.note.note_type == crate::midi_note::MIDINoteType::Rest && rest.start == 0 => *rest,
+        _ => return,
+    };
+    let division = division as u32;
+    if division == 0 || rest.duration / division * division != rest.duration {
+        return;
+    }
+    let count = rest.duration / division;
+    if count as u64 > MAX_HASH_RESTS {
+        return;
+    }
+    track.notes = (0..count)
+        .map(|idx| crate::midi_note::MIDITimedNote::new(rest.note, idx * division, division))
+        .collect::<Vec<crate::midi_note::MIDITimedNote>>();
+}
+
+/// Generate MIDI note number of `note` (other than a rest), failing if it is outside of the MIDI note range
+fn gen_note_number(note: &crate::midi_note::MIDINote) -> Result<u8, crate::midi_note::MIDINoteRangeError> {
+    note.convert().ok_or(crate::midi_note::MIDINoteRangeError::OutOfRange {
+        note_type: note.note_type,
+        octave: note.octave,
+    })
+}
+
+/// Velocity curve
+///
+/// Determines the velocity of each note in a [MIDITrack](struct.MIDITrack.html) that
+/// does not have its own velocity, allowing generated melodies to carry dynamics.
+/// Velocities outside of 1 to 127 are clamped (see: [MIDITrack::gen_velocities](struct.MIDITrack.html#method.gen_velocities)).
+///
+/// # Examples
+///
+/// ```rust
+/// let mut track = libatm::MIDITrack::new("C:4,D:4,E:4,F:4,G:4".parse::<libatm::MIDINoteVec>().unwrap(), 1);
+/// // Crescendo from pianissimo to fortissimo
+/// track.velocity_curve = Some(libatm::MIDIVelocityCurve::Linear { from: 0x20, to: 0x70 });
+/// assert_eq!(vec![0x20, 0x34, 0x48, 0x5c, 0x70], track.gen_velocities());
+/// // Accent every other note
+/// track.velocity_curve = Some(libatm::MIDIVelocityCurve::Cycle(vec![0x70, 0x40]));
+/// assert_eq!(vec![0x70, 0x40, 0x70, 0x40, 0x70], track.gen_velocities());
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MIDIVelocityCurve {
+    /// Interpolate linearly between two velocities, from the first note pressed to the last
+    Linear { from: u8, to: u8 },
+    /// Repeat a pattern of velocities (in the order notes are pressed)
+    Cycle(Vec<u8>),
+}
+
+/// MIDI track
+///
+/// Represents the contents of a single track chunk: a set of [timed notes](../midi_note/struct.MIDITimedNote.html)
+/// played on one channel, preceded by any meta events and channel messages.  Notes may start
+/// at the same tick (chords) and overlap.  A track without notes can be used as the tempo map
+/// (A.K.A. conductor track) of a format 1 file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MIDITrack {
+    /// Notes to generate the track chunk from (ordered by start tick when written)
+    pub notes: Vec<crate::midi_note::MIDITimedNote>,
+    /// Channel on which to play the notes (`0` by default)
+    pub channel: u8,
+    /// Velocity with which to play notes without a velocity (`0x64` by default, clamped between 1 and 127)
+    pub velocity: u8,
+    /// Velocity curve to apply to notes without a velocity (overrides `velocity`)
+    pub velocity_curve: Option<MIDIVelocityCurve>,
+    /// Meta events (tempo, time signature, track name, etc.) to write at the start of the track chunk
+    pub meta_events: Vec<MIDIMetaEvent>,
+    /// Channel messages (program change, control change, etc.) to write at the start
+    /// of the track chunk, after any meta events
+    pub channel_messages: Vec<MIDIChannelMessage>,
+}
+
+impl MIDITrack {
+    /// Create new `MIDITrack` from sequence of notes
+    ///
+    /// Each note is played for `division` ticks, one after another.
+    pub fn new(sequence: crate::midi_note::MIDINoteVec, division: u16) -> Self {
+        let division = division as u32;
+        let notes = sequence
+            .iter()
+            .enumerate()
+            .map(|(idx, note)| crate::midi_note::MIDITimedNote::new(*note, gen_start(idx, division), division))
+            .collect::<Vec<crate::midi_note::MIDITimedNote>>();
+        Self::from_notes(notes)
+    }
+
+    /// Create new `MIDITrack` from timed notes
+    pub fn from_notes(notes: Vec<crate::midi_note::MIDITimedNote>) -> Self {
+        Self {
+            notes,
+            channel: 0,
+            velocity: 0x64,
+            velocity_curve: None,
+            meta_events: Vec::new(),
+            channel_messages: Vec::new(),
+        }
+    }
+
+    /// Create new `MIDITrack` from sequence of chords
+    ///
+    /// All notes in each chord are played simultaneously for `division` ticks, and
+    /// each chord is played after the previous one is released.
+    ///
+    /// # Examples
     ///
-    /// This hash function simply concatenates the sequential integer
-    /// representation of the file's sequence of `MIDINote`.  By this definition,
-    /// no two non-identical sequences can have the same hash.  The primary
-    /// intended purpose of this function is to allow for O(1) lookups by note sequence
-    /// once a file has been written to disk.
-    pub fn gen_hash(&self) -> String {
+    /// ```rust
+    /// let chords = vec!["C:4,E:4,G:4", "F:4,A:4,C:5", "G:4,B:4,D:5"]
+    ///     .into_iter()
+    ///     .map(|chord| chord.parse::<libatm::MIDINoteVec>().unwrap())
+    ///     .collect::<Vec<libatm::MIDINoteVec>>();
+    /// let track = libatm::MIDITrack::from_chords(chords, 1);
+    /// assert_eq!(9, track.notes.len());
+    /// assert_eq!(2, track.notes[8].start);
+    /// ```
+    pub fn from_chords(chords: Vec<crate::midi_note::MIDINoteVec>, division: u16) -> Self {
+        let division = division as u32;
+        let notes = chords
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, chord)| {
+                chord
+                    .iter()
+                    .map(move |note| crate::midi_note::MIDITimedNote::new(*note, gen_start(idx, division), division))
+            })
+            .collect::<Vec<crate::midi_note::MIDITimedNote>>();
+        Self::from_notes(notes)
+    }
+
+    /// Generate sequence of notes in this track, ordered by start tick
+    pub fn gen_sequence(&self) -> crate::midi_note::MIDINoteVec {
         self
-            .sequence
+            .gen_sorted_notes()
             .iter()
-            .map(|note| note.convert().to_string())
-            .collect::<Vec<String>>()
-            .join("")
+            .map(|note| note.note)
+            .collect::<crate::midi_note::MIDINoteVec>()
     }
 
-    /// Generate header chunk (see: [MIDIHeader](struct.MIDIHeader.html))
-    pub fn gen_header(&self) -> MIDIHeader {
-        MIDIHeader::new(
-            vec![0x4d, 0x54, 0x68, 0x64], // 'MThd'
-            0x06,
-            self.format,
-            self.tracks,
-            self.division,
-        )
+    /// Whether every note is held for the same number of ticks and starts
+    /// when the previous note is released (A.K.A. a melody without rhythm)
+    pub fn is_uniform(&self) -> bool {
+        let notes = self.gen_sorted_notes();
+        let duration = notes.first().map_or(0, |note| note.duration);
+        notes
+            .iter()
+            .enumerate()
+            .all(|(idx, note)| note.duration == duration && note.start as u64 == idx as u64 * duration as u64)
     }
 
-    /// Generate the size of this track chunk header in bytes (on disk)
-    pub fn gen_track_size(&self) -> u32 {
-        gen_midi_track_size(self.sequence.len() as u32)
+    /// Generate unique hash for this track's `MIDINote` sequence
+    ///
+    /// See [MIDIFile::gen_hash](struct.MIDIFile.html#method.gen_hash).
+    pub fn gen_hash(&self) -> Result<String, crate::midi_note::MIDINoteRangeError> {
+        self.gen_layout_hash()
     }
 
-    /// Generate track chunk header (see: [MIDITrackHeader](struct.MIDITrackHeader.html))
-    pub fn gen_track_header(&self) -> MIDITrackHeader {
-        MIDITrackHeader::new(
-            vec![0x4d, 0x54, 0x72, 0x6b], // 'MTrk'
-            self.gen_track_size(),
-        )
+    /// Generate hash for this track's `MIDINote` sequence using the legacy scheme
+    ///
+    /// See [MIDIFile::gen_legacy_hash](struct.MIDIFile.html#method.gen_legacy_hash).
+    pub fn gen_legacy_hash(&self) -> Result<String, crate::midi_note::MIDINoteRangeError> {
+        let numbers = self
+            .gen_sorted_notes()
+            .iter()
+            .map(|note| match note.note.note_type {
+                crate::midi_note::MIDINoteType::Rest => Ok(u32::MAX.to_string()),
+                _ => gen_note_number(&note.note).map(|number| number.to_string()),
+            })
+            .collect::<Result<Vec<String>, crate::midi_note::MIDINoteRangeError>>()?;
+        Ok(numbers.join(""))
+    }
+
+    /// Generate hash from the notes (other than rests) and the end of this track (see: [MIDIFile::gen_hash](struct.MIDIFile.html#method.gen_hash))
+    fn gen_layout_hash(&self) -> Result<String, crate::midi_note::MIDINoteRangeError> {
+        let mut notes = self
+            .gen_sorted_notes()
+            .into_iter()
+            .filter(|note| note.note.note_type != crate::midi_note::MIDINoteType::Rest)
+            .collect::<Vec<&crate::midi_note::MIDITimedNote>>();
+        // Tracks made only of rests are hashed from the rests themselves
+        if notes.is_empty() {
+            notes = self.gen_sorted_notes();
+        }
+        let gen_end = |note: &&crate::midi_note::MIDITimedNote| note.start as u64 + note.duration as u64;
+        let end = self.notes.iter().map(|note| gen_end(&note)).max().unwrap_or(0);
+        let gen_number = |note: &&crate::midi_note::MIDITimedNote| match note.note.note_type {
+            crate::midi_note::MIDINoteType::Rest => Ok(String::from("r")),
+            _ => gen_note_number(&note.note).map(|number| number.to_string()),
+        };
+
+        match gen_grid_duration(&notes, end) {
+            Some(0) => Ok(notes.iter().map(gen_number).collect::<Result<Vec<String>, _>>()?.join("-")),
+            Some(duration) => {
+                let mut notes = notes.iter().peekable();
+                let slots = (0..end / duration as u64)
+                    .map(|slot| match notes.next_if(|note| note.start as u64 == slot * duration as u64) {
+                        Some(note) => gen_number(note),
+                        None => Ok(String::from("r")),
+                    })
+                    .collect::<Result<Vec<String>, _>>()?;
+                Ok(slots.join("-"))
+            },
+            None => {
+                let mut hash = notes.iter().map(gen_number).collect::<Result<Vec<String>, _>>()?.join("-");
+                for note in notes.iter() {
+                    hash.push_str(&format!("_{}-{}", note.start, note.duration));
+                }
+                // Silence at the end of the track
+                if end > notes.iter().map(gen_end).max().unwrap_or(0) {
+                    hash.push_str(&format!("_{}", end));
+                }
+                Ok(hash)
+            },
+        }
+    }
+
+    /// Generate references to the notes in this track, ordered by start
+    /// tick (stable for notes pressed at the same tick)
+    fn gen_sorted_notes(&self) -> Vec<&crate::midi_note::MIDITimedNote> {
+        let mut notes = self.notes.iter().collect::<Vec<&crate::midi_note::MIDITimedNote>>();
+        notes.sort_by_key(|note| note.start);
+        notes
+    }
+
+    /// Generate velocity of each note in this track (in the same order as `notes`)
+    ///
+    /// Notes with their own velocity keep it, otherwise the velocity is taken from the
+    /// velocity curve (if set) or the track velocity.  Velocities are clamped between 1 and 127,
+    /// as a `NoteOn` event with velocity 0 releases the note instead of pressing it.
+    pub fn gen_velocities(&self) -> Vec<u8> {
+        // Order in which notes are pressed (stable for notes pressed at the same tick)
+        let mut order = (0..self.notes.len()).collect::<Vec<usize>>();
+        order.sort_by_key(|idx| self.notes[*idx].start);
+        let first_start = order.first().map_or(0, |idx| self.notes[*idx].start);
+        let last_start = order.last().map_or(0, |idx| self.notes[*idx].start);
+
+        let mut velocities = vec![self.velocity; self.notes.len()];
+        for (position, idx) in order.iter().enumerate() {
+            let note = &self.notes[*idx];
+            velocities[*idx] = match (note.velocity, &self.velocity_curve) {
+                (Some(velocity), _) => velocity,
+                (None, Some(MIDIVelocityCurve::Linear { from, to })) => match last_start - first_start {
+                    0 => *from,
+                    span => {
+                        let offset = (note.start - first_start) as i64;
+                        let range = *to as i64 - *from as i64;
+                        (*from as i64 + (range * offset + (span as i64 / 2) * range.signum()) / span as i64) as u8
+                    },
+                },
+                (None, Some(MIDIVelocityCurve::Cycle(pattern))) if !pattern.is_empty() => {
+                    pattern[position % pattern.len()]
+                },
+                _ => self.velocity,
+            }
+            .clamp(1, 0x7f);
+        }
+        velocities
+    }
+
+    /// Generate meta events to write at the start of the track chunk
+    ///
+    /// Any `EndOfTrack` meta events are skipped, as the End of Track meta event is
+    /// written automatically (unless writing a minimal file).
+    pub fn gen_meta_events(&self) -> Vec<MIDITrackEvent> {
+        self
+            .meta_events
+            .iter()
+            .filter(|event| **event != MIDIMetaEvent::EndOfTrack)
+            .map(|event| MIDITrackEvent {
+                delta_time: 0,
+                event: MIDIEvent::Meta(event.clone()),
+            })
+            .collect::<Vec<MIDITrackEvent>>()
     }
 
-    /// Generate track data (see: [MIDIChannelVoiceMessage](../midi_event/struct.MIDIChannelVoiceMessage.html))
-    pub fn gen_track(&self) -> Vec<MIDIChannelVoiceMessage> {
-        let delta_time = self.division as u8;
+    /// Generate channel messages to write at the start of the track chunk
+    pub fn gen_channel_messages(&self) -> Vec<MIDITrackEvent> {
         self
-            .sequence
+            .channel_messages
+            .iter()
+            .map(|message| MIDITrackEvent {
+                delta_time: 0,
+                event: MIDIEvent::Channel(*message),
+            })
+            .collect::<Vec<MIDITrackEvent>>()
+    }
+
+    /// Generate note data (see: [MIDIChannelVoiceMessage](../midi_event/struct.MIDIChannelVoiceMessage.html))
+    ///
+    /// Each note produces a `NoteOn` event at its start tick (with the velocity from
+    /// [gen_velocities](#method.gen_velocities)) and a `NoteOn` event with
+    /// velocity 0 (equivalent to `NoteOff`) at its end tick.  Events are ordered by tick,
+    /// with notes released before notes pressed at the same tick (except for notes with
+    /// a duration of 0).  Only the first event includes a status byte, all others use running status.
+    /// Rests do not produce any events, the silence is encoded in the delta time of the next event.
+    /// Fails if a note is outside of the MIDI note range, if a note is released after the last tick
+    /// (`u32::MAX`), or if the time between two events cannot be encoded as a delta time
+    /// (see: [MAX_VLQ](../midi_event/constant.MAX_VLQ.html)).
+    pub fn gen_notes(&self) -> Result<Vec<MIDIChannelVoiceMessage>, WriteMIDIFileError> {
+        // (tick, order at tick, note, velocity)
+        let mut events = Vec::with_capacity(self.notes.len() * 2);
+        for (note, velocity) in self.notes.iter().zip(self.gen_velocities()) {
+            if note.note.note_type == crate::midi_note::MIDINoteType::Rest {
+                continue;
+            }
+            let release_order = match note.duration {
+                0 => 2,
+                _ => 0,
+            };
+            gen_note_number(&note.note)?;
+            let end = note.end().ok_or(WriteMIDIFileError::TickOverflow)?;
+            events.push((note.start, 1, note.note, velocity));
+            events.push((end, release_order, note.note, 0));
+        }
+        events.sort_by_key(|(tick, order, _, _)| (*tick, *order));
+
+        let mut last_tick = 0;
+        events
             .iter()
             .enumerate()
-            .map(|(idx, note)| {
-                let first_status = match idx {
+            .map(|(idx, (tick, _, note, velocity))| {
+                let status = match idx {
                     0 => MIDIStatus::NoteOn,
                     _ => MIDIStatus::RunningStatus,
                 };
-                vec![
-                    MIDIChannelVoiceMessage::new(0, &note, 0x64, first_status, 0,),
-                    MIDIChannelVoiceMessage::new(delta_time, &note, 0, MIDIStatus::RunningStatus, 0,)
-                ]
+                let delta_time = tick - last_tick;
+                if delta_time > crate::midi_event::MAX_VLQ {
+                    return Err(WriteMIDIFileError::DeltaTimeOverflow { delta_time });
+                }
+                last_tick = *tick;
+                Ok(MIDIChannelVoiceMessage::new(delta_time, note, *velocity, status, self.channel))
             })
-            .flatten()
-            .collect::<Vec<MIDIChannelVoiceMessage>>()
+            .collect::<Result<Vec<MIDIChannelVoiceMessage>, WriteMIDIFileError>>()
     }
 
-    /// Generate the size of this MIDI file in bytes (on disk)
-    pub fn gen_size(&self) -> u32 {
-        gen_midi_file_size(self.sequence.len() as u32)
+    /// Generate End of Track meta event
+    ///
+    /// If the track ends with rests, the End of Track meta event is delayed
+    /// until the last rest is over.  Fails under the same conditions as [gen_notes](#method.gen_notes).
+    pub fn gen_end_of_track(&self) -> Result<MIDITrackEvent, WriteMIDIFileError> {
+        let delta_time = self.gen_end_of_track_delta().ok_or(WriteMIDIFileError::TickOverflow)?;
+        if delta_time > crate::midi_event::MAX_VLQ {
+            return Err(WriteMIDIFileError::DeltaTimeOverflow { delta_time });
+        }
+        Ok(MIDITrackEvent {
+            delta_time,
+            event: MIDIEvent::Meta(MIDIMetaEvent::EndOfTrack),
+        })
     }
 
-    /// Write MIDI file to buffer
-    pub fn write_buffer<T>(&self, target: &mut T) -> std::io::Result<()>
+    /// Generate number of ticks between the last note and the end of the last rest
+    /// (`None` if a note is released after the last tick)
+    fn gen_end_of_track_delta(&self) -> Option<u32> {
+        let (note_end, rest_end) = self.notes.iter().try_fold((0, 0), |(note_end, rest_end), note| {
+            match note.note.note_type {
+                crate::midi_note::MIDINoteType::Rest => Some((note_end, rest_end.max(note.end()?))),
+                _ => Some((note_end.max(note.end()?), rest_end)),
+            }
+        })?;
+        Some(rest_end.saturating_sub(note_end))
+    }
+
+    /// Generate the size of this track chunk in bytes (on disk, excluding the track chunk header)
+    ///
+    /// The size is computed without generating the note events.  The size of a track
+    /// that cannot be written (see: [gen_notes](#method.gen_notes)) is unspecified.
+    pub fn gen_size(&self, minimal: bool) -> u32 {
+        let setup_size = self
+            .gen_meta_events()
+            .iter()
+            .chain(self.gen_channel_messages().iter())
+            .map(|event| event.gen_size())
+            .sum::<u32>();
+        let end_of_track_size = match minimal {
+            true => 0,
+            false => 3 + crate::midi_event::gen_vlq_size(self.gen_end_of_track_delta().unwrap_or(0)),
+        };
+        setup_size + self.gen_notes_size() + end_of_track_size
+    }
+
+    /// Generate the size of the note events in bytes (see: [gen_notes](#method.gen_notes))
+    fn gen_notes_size(&self) -> u32 {
+        let notes = || {
+            self
+                .notes
+                .iter()
+                .filter(|note| note.note.note_type != crate::midi_note::MIDINoteType::Rest)
+        };
+        // Each note is pressed and released with two data bytes,
+        // and the first event includes the status byte
+        let data_size = match notes().count() as u32 {
+            0 => 0,
+            count => count.saturating_mul(4).saturating_add(1),
+        };
+
+        // The delta times only depend on the ticks at which notes are pressed and released.
+        // Notes are usually played one after another, in which case the ticks are already in order.
+        let mut last_tick = 0;
+        let mut delta_times_size = 0u32;
+        for note in notes() {
+            if note.start < last_tick {
+                let mut ticks = notes()
+                    .map(|note| note.start)
+                    .chain(notes().map(|note| note.start.saturating_add(note.duration)))
+                    .collect::<Vec<u32>>();
+                ticks.sort_unstable();
+                last_tick = 0;
+                delta_times_size = 0;
+                for tick in ticks {
+                    delta_times_size = delta_times_size.saturating_add(crate::midi_event::gen_vlq_size(tick - last_tick));
+                    last_tick = tick;
+                }
+                break;
+            }
+            let end = note.start.saturating_add(note.duration);
+            delta_times_size = delta_times_size
+                .saturating_add(crate::midi_event::gen_vlq_size(note.start - last_tick))
+                .saturating_add(crate::midi_event::gen_vlq_size(end - note.start));
+            last_tick = end;
+        }
+        data_size.saturating_add(delta_times_size)
+    }
+
+    /// Generate track chunk header (see: [MIDITrackHeader](struct.MIDITrackHeader.html))
+    pub fn gen_header(&self, minimal: bool) -> MIDITrackHeader {
+        MIDITrackHeader::new(
+            vec![0x4d, 0x54, 0x72, 0x6b], // 'MTrk'
+            self.gen_size(minimal),
+        )
+    }
+
+    /// Write track chunk (including header) to buffer
+    ///
+    /// Fails with [InvalidInput](https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput)
+    /// if the note events cannot be generated (see: [gen_notes](#method.gen_notes)).
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: buffer to write to
+    /// * `minimal`: whether to omit the End of Track meta event
+    pub fn write_buffer<T>(&self, target: &mut T, minimal: bool) -> std::io::Result<()>
     where
         T: byteorder::WriteBytesExt,
     {
-        let header = self.gen_header();
-        header.write_buffer(target)?;
-
-        let track_header = self.gen_track_header();
-        track_header.write_buffer(target)?;
+        // Generate the note events once, and compute the track chunk length from them
+        let invalid_input = |err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err);
+        let mut setup_events = self.gen_meta_events();
+        setup_events.extend(self.gen_channel_messages());
+        let notes = self.gen_notes().map_err(invalid_input)?;
+        let end_of_track = match minimal {
+            true => None,
+            false => Some(self.gen_end_of_track().map_err(invalid_input)?),
+        };
+        let length = setup_events.iter().map(|event| event.gen_size()).sum::<u32>()
+            + notes.iter().map(|event| event.gen_size()).sum::<u32>()
+            + end_of_track.as_ref().map_or(0, |event| event.gen_size());
 
-        let track = self.gen_track();
-        for event in track.iter() {
+        MIDITrackHeader::new(
+            vec![0x4d, 0x54, 0x72, 0x6b], // 'MTrk'
+            length,
+        ).write_buffer(target)?;
+        for event in setup_events.iter() {
+            event.write_buffer(target)?;
+        }
+        for event in notes.iter() {
+            event.write_buffer(target)?;
+        }
+        if let Some(event) = end_of_track {
             event.write_buffer(target)?;
         }
         Ok(())
     }
 
-    /// Write MIDI file to path on disk
-    pub fn write_file<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
-        let target_file = std::fs::File::create(path)?;
-        let mut target_file = std::io::BufWriter::new(target_file);
-        self.write_buffer(&mut target_file)?;
-        Ok(())
+    /// Create `MIDITrack` from track chunk
+    ///
+    /// Notes are reconstructed by pairing each `NoteOn` event (with non-zero velocity) with the
+    /// next `NoteOff` (or `NoteOn` with velocity 0) event for the same note, in the order the
+    /// notes are pressed (i.e., ordered by start tick).  Notes that are never released are held
+    /// until the last event in the track.  The channel is taken from the first `NoteOn` event,
+    /// as is the track velocity.  Notes pressed with any other velocity keep their own velocity,
+    /// so no velocity is lost.  `NoteOff` events on other channels are ignored.  Meta events (other
+    /// than End of Track) and channel messages (other than `NoteOn` and `NoteOff`) are collected in
+    /// order.  All other events are ignored.  Silence after the last note (i.e. a delayed End of Track
+    /// meta event) is read as a rest.  Fails if notes are pressed on more than one channel (a `MIDITrack`
+    /// plays all of its notes on a single channel), or if the track is longer than the maximum number
+    /// of ticks (`u32::MAX`).
+    pub fn from_chunk(chunk: &MIDITrackChunk) -> Result<Self, ReadMIDIFileError> {
+        let mut track = Self::from_notes(Vec::new());
+        let mut channel = None;
+        let mut track_velocity = None;
+        // Indices (into track.notes) of pressed notes by note number
+        let mut pressed: Vec<std::collections::VecDeque<usize>> = vec![std::collections::VecDeque::new(); 128];
+        let mut tick = 0u32;
+        for event in chunk.events.iter() {
+            tick = tick.checked_add(event.delta_time).ok_or(ReadMIDIFileError::TickOverflow)?;
+            match &event.event {
+                MIDIEvent::Channel(MIDIChannelMessage::NoteOn { channel: note_channel, note, velocity })
+                    if *velocity > 0 =>
+                {
+                    let first = *channel.get_or_insert(*note_channel);
+                    if *note_channel != first {
+                        return Err(ReadMIDIFileError::MixedChannels { first, second: *note_channel });
+                    }
+                    let midi_note = <crate::midi_note::MIDINote as std::convert::TryFrom<u8>>::try_from(*note)
+                        .map_err(|_| ReadMIDIFileError::UnrepresentableNote(*note))?;
+                    pressed[*note as usize].push_back(track.notes.len());
+                    let mut timed_note = crate::midi_note::MIDITimedNote::new(midi_note, tick, 0);
+                    if *velocity != *track_velocity.get_or_insert(*velocity) {
+                        timed_note.velocity = Some(*velocity);
+                    }
+                    track.notes.push(timed_note);
+                },
+                MIDIEvent::Channel(MIDIChannelMessage::NoteOn { channel: note_channel, note, .. })
+                | MIDIEvent::Channel(MIDIChannelMessage::NoteOff { channel: note_channel, note, .. })
+                    if channel == Some(*note_channel) =>
+                {
+                    if let Some(idx) = pressed[*note as usize].pop_front() {
+                        track.notes[idx].duration = tick - track.notes[idx].start;
+                    }
+                },
+                // Released without being pressed on this channel
+                MIDIEvent::Channel(MIDIChannelMessage::NoteOn { .. })
+                | MIDIEvent::Channel(MIDIChannelMessage::NoteOff { .. }) => (),
+                MIDIEvent::Channel(message) => track.channel_messages.push(*message),
+                MIDIEvent::Meta(MIDIMetaEvent::EndOfTrack) => (),
+                MIDIEvent::Meta(meta_event) => track.meta_events.push(meta_event.clone()),
+                _ => (),
+            }
+        }
+        for idx in pressed.into_iter().flatten() {
+            track.notes[idx].duration = tick - track.notes[idx].start;
+        }
+        let note_end = track.notes.iter().filter_map(|note| note.end()).max().unwrap_or(0);
+        if tick > note_end {
+            let rest = crate::midi_note::MIDINote::new(crate::midi_note::MIDINoteType::Rest, 0);
+            track.notes.push(crate::midi_note::MIDITimedNote::new(rest, note_end, tick - note_end));
+        }
+        track.channel = channel.unwrap_or(0);
+        track.velocity = track_velocity.unwrap_or(track.velocity);
+        Ok(track)
     }
+}
 
-    /// Generate buffer containing entire MIDI file
-    pub fn gen_file(&self) -> std::io::Result<Vec<u8>> {
-        let mut buffer = Vec::with_capacity(self.gen_size() as usize);
-        self.write_buffer(&mut buffer)?;
-        Ok(buffer)
+/// MIDI file representation
+///
+/// MIDI files can be complex, allowing for any number of tracks with
+/// different notes and instruments playing simultaneously.  This library
+/// was created for the express purpose of brute-forcing melodies, and thus
+/// only supports a subset of the official MIDI standard.  More specifically,
+/// this implementation is optimized to create the smallest possible single track MIDI
+/// files, but also supports format 1 files with multiple [tracks](struct.MIDITrack.html)
+/// (e.g., a tempo track followed by one track per instrument) and format 2 files with
+/// multiple independent patterns (e.g., a batch of generated melodies).  Set `minimal` to omit
+/// the End of Track meta event, which produces files identical to those written by earlier
+/// versions of this library at the cost of violating the MIDI spec.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MIDIFile {
+    /// Tracks to generate the track chunks from (format 0 files must have exactly one)
+    pub tracks: Vec<MIDITrack>,
+    /// Format specification
+    pub format: MIDIFormat,
+    /// Number of ticks to represent a quarter-note (recommended to use `1`, as
+    /// divisions above `127` require multi-byte delta times)
+    pub division: u16,
+    /// Omit End of Track meta event from track chunks (legacy behavior, `false` by default)
+    pub minimal: bool,
+}
+
+impl MIDIFile {
+    /// Create new single track `MIDIFile`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mfile = libatm::MIDIFile::new(
+    ///     "C:4,CSharp:8,D:5,DSharp:3".parse::<libatm::MIDINoteVec>().unwrap(),
+    ///     libatm::MIDIFormat::Format0,
+    ///     1,
+    /// );
+    /// assert_eq!("60-109-74-51", mfile.gen_hash().unwrap());
+    /// ```
+    pub fn new(
+        sequence: crate::midi_note::MIDINoteVec,
+        format: MIDIFormat,
+        division: u16,
+    ) -> MIDIFile {
+        MIDIFile::from_tracks(vec![MIDITrack::new(sequence, division)], format, division)
+    }
+
+    /// Create new single track `MIDIFile` from notes with rhythm
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mfile = libatm::MIDIFile::from_timed_notes(
+    ///     "C:4/2,D:4/1,E:4/1".parse::<libatm::MIDITimedNoteVec>().unwrap(),
+    ///     libatm::MIDIFormat::Format0,
+    ///     1,
+    /// );
+    /// assert_eq!("60-62-64_0-2_2-1_3-1", mfile.gen_hash().unwrap());
+    /// ```
+    pub fn from_timed_notes(
+        notes: crate::midi_note::MIDITimedNoteVec,
+        format: MIDIFormat,
+        division: u16,
+    ) -> MIDIFile {
+        MIDIFile::from_tracks(vec![MIDITrack::from_notes(notes.0)], format, division)
+    }
+
+    /// Create new `MIDIFile` from tracks
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// // Tempo track followed by piano and bass tracks
+    /// let mut tempo_track = libatm::MIDITrack::from_notes(Vec::new());
+    /// tempo_track.meta_events.push(libatm::MIDIMetaEvent::from_bpm(90));
+    /// let piano_track = libatm::MIDITrack::new("C:4,E:4,G:4".parse::<libatm::MIDINoteVec>().unwrap(), 1);
+    /// let mut bass_track = libatm::MIDITrack::new("C:2,C:2,G:2".parse::<libatm::MIDINoteVec>().unwrap(), 1);
+    /// bass_track.channel = 1;
+    /// bass_track.channel_messages.push(libatm::MIDIChannelMessage::ProgramChange { channel: 1, program: 32 });
+    /// let mfile = libatm::MIDIFile::from_tracks(
+    ///     vec![tempo_track, piano_track, bass_track],
+    ///     libatm::MIDIFormat::Format1,
+    ///     1,
+    /// );
+    /// assert_eq!(3, mfile.gen_header().tracks);
+    /// ```
+    pub fn from_tracks(tracks: Vec<MIDITrack>, format: MIDIFormat, division: u16) -> MIDIFile {
+        MIDIFile {
+            tracks,
+            format,
+            division,
+            minimal: false,
+        }
+    }
+
+    /// Create new format 2 `MIDIFile` from independent patterns
+    ///
+    /// Each pattern is written as its own track chunk, and should contain any meta
+    /// events (tempo, time signature, etc.) it needs, as format 2 tracks do not share
+    /// a tempo map.  A `SequenceNumber` meta event with the index of the pattern is added
+    /// to the start of each pattern that does not already have one, so patterns can be
+    /// identified after the file has been written.  Fails if there are more patterns than
+    /// the header chunk can count (65535), as sequence numbers would no longer be unique.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let patterns = vec!["C:4,D:4,E:4", "E:4,D:4,C:4"]
+    ///     .into_iter()
+    ///     .map(|melody| libatm::MIDITrack::new(melody.parse::<libatm::MIDINoteVec>().unwrap(), 1))
+    ///     .collect::<Vec<libatm::MIDITrack>>();
+    /// let mfile = libatm::MIDIFile::from_patterns(patterns, 1).unwrap();
+    /// let buffer = mfile.gen_file().unwrap();
+    /// let observed = libatm::MIDIFile::from_bytes(&buffer).unwrap().split_patterns();
+    /// assert_eq!(2, observed.len());
+    /// assert_eq!("64-62-60", observed[1].gen_hash().unwrap());
+    /// ```
+    pub fn from_patterns(patterns: Vec<MIDITrack>, division: u16) -> Result<MIDIFile, WriteMIDIFileError> {
+        if patterns.len() > u16::MAX as usize {
+            return Err(WriteMIDIFileError::TooManyTracks { tracks: patterns.len() });
+        }
+        let tracks = patterns
+            .into_iter()
+            .enumerate()
+            .map(|(idx, mut pattern)| {
+                let has_sequence_number = pattern
+                    .meta_events
+                    .iter()
+                    .any(|event| matches!(event, MIDIMetaEvent::SequenceNumber(_)));
+                if !has_sequence_number {
+                    pattern.meta_events.insert(0, MIDIMetaEvent::SequenceNumber(idx as u16));
+                }
+                pattern
+            })
+            .collect::<Vec<MIDITrack>>();
+        Ok(MIDIFile::from_tracks(tracks, MIDIFormat::Format2, division))
+    }
+
+    /// Set velocity of every track in this file
+    ///
+    /// Notes with their own velocity, and tracks with a velocity curve, are not affected.
+    pub fn set_velocity(&mut self, velocity: u8) {
+        for track in self.tracks.iter_mut() {
+            track.velocity = velocity;
+        }
+    }
+
+    /// Split `MIDIFile` into one single track (format 0) `MIDIFile` per track
+    ///
+    /// Intended for format 2 files, where each track is an independent pattern.  Tracks
+    /// of format 1 files are not independent (they share the tempo map of the first track),
+    /// so splitting them drops tempo and time signature information from all but the first track.
+    pub fn split_patterns(&self) -> Vec<MIDIFile> {
+        self
+            .tracks
+            .iter()
+            .map(|track| {
+                let mut pattern = MIDIFile::from_tracks(vec![track.clone()], MIDIFormat::Format0, self.division);
+                pattern.minimal = self.minimal;
+                pattern
+            })
+            .collect::<Vec<MIDIFile>>()
+    }
+
+    /// Generate unique hash for this file's `MIDINote` sequence
+    ///
+    /// This hash function joins the integer representation of each `MIDINote` in a track
+    /// with `-`, and joins the tracks of the file with `.`.  The hash only depends on how the track
+    /// is written, so a track hashes to the same value after being read back from disk.  If every note
+    /// is held for the same number of ticks and pressed at a multiple of it, one after another, the
+    /// silence between notes (and at the end of the track) is represented by one `r` per rest of the same
+    /// length (A.K.A. a melody without rhythm).  Otherwise, the start tick and duration of each note
+    /// (other than rests) are appended as `_<start>-<duration>`, followed by `_<end>` if the track ends
+    /// with silence after the last note, so melodies with the same notes but a different rhythm have
+    /// different hashes.  Tracks made only of rests are hashed from the rests themselves (one `r` per rest,
+    /// followed by their rhythm if they are not uniform), so the number of rests is kept.
+    /// Melodies without rhythm hash to the same value regardless of `division`.
+    /// Because every field is delimited, no two non-identical sequences can have the same hash,
+    /// and the sequence can be recovered with [decode_hash](#method.decode_hash).
+    /// The primary intended purpose of this function is to allow for O(1) lookups by note sequence
+    /// once a file has been written to disk.  For format 2 files, the hash of each
+    /// pattern can be generated with [MIDITrack::gen_hash](struct.MIDITrack.html#method.gen_hash).
+    /// Fails if a note is outside of the MIDI note range (see: [MIDINote::convert](../midi_note/struct.MIDINote.html#method.convert)).
+    pub fn gen_hash(&self) -> Result<String, crate::midi_note::MIDINoteRangeError> {
+        let hashes = self
+            .tracks
+            .iter()
+            .map(|track| track.gen_hash())
+            .collect::<Result<Vec<String>, crate::midi_note::MIDINoteRangeError>>()?;
+        Ok(hashes.join("."))
+    }
+
+    /// Generate hash for this file's `MIDINote` sequence using the legacy scheme
+    ///
+    /// The legacy scheme simply concatenates the integer representation of each `MIDINote`
+    /// (across all tracks, in order of start tick), with `u32::MAX` representing a rest.  Rhythm
+    /// is ignored.  Without delimiters, different sequences can have the same hash (i.e. `[12, 112]`
+    /// and `[121, 12]` both hash to `"12112"`), so this should only be used to look up files written
+    /// with earlier versions of this library.  Fails under the same conditions as [gen_hash](#method.gen_hash).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mfile = libatm::MIDIFile::new(
+    ///     "C:4,CSharp:8,D:5,DSharp:3".parse::<libatm::MIDINoteVec>().unwrap(),
+    ///     libatm::MIDIFormat::Format0,
+    ///     1,
+    /// );
+    /// assert_eq!("601097451", mfile.gen_legacy_hash().unwrap());
+    /// ```
+    pub fn gen_legacy_hash(&self) -> Result<String, crate::midi_note::MIDINoteRangeError> {
+        let hashes = self
+            .tracks
+            .iter()
+            .map(|track| track.gen_legacy_hash())
+            .collect::<Result<Vec<String>, crate::midi_note::MIDINoteRangeError>>()?;
+        Ok(hashes.join(""))
+    }
+
+    /// Decode hash generated by [gen_hash](#method.gen_hash) into a `MIDINote` sequence
+    ///
+    /// Returns the notes of every track concatenated in order, so the track boundaries of
+    /// multi-track files are lost.  To decode each track separately, split the hash on `.`
+    /// and decode each part (every part is itself a valid single track hash).  Rhythm is
+    /// validated (there must be either none, or exactly one `_<start>-<duration>` per note
+    /// optionally followed by `_<end>`) but not returned, and rests (including the rests of tracks
+    /// made only of rests) are decoded with octave `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let sequence = "C:4,CSharp:8,D:5,DSharp:3".parse::<libatm::MIDINoteVec>().unwrap();
+    /// let mfile = libatm::MIDIFile::new(sequence.clone(), libatm::MIDIFormat::Format0, 1);
+    /// assert_eq!("60-109-74-51", mfile.gen_hash().unwrap());
+    /// assert_eq!(sequence, libatm::MIDIFile::decode_hash(&mfile.gen_hash().unwrap()).unwrap());
+    ///
+    /// // Decode each track of a multi-track file separately
+    /// let tracks = "60-64.67-72"
+    ///     .split('.')
+    ///     .map(|track_hash| libatm::MIDIFile::decode_hash(track_hash).unwrap())
+    ///     .collect::<Vec<libatm::MIDINoteVec>>();
+    /// assert_eq!("G:4,C:5", tracks[1].to_string());
+    /// ```
+    pub fn decode_hash(hash: &str) -> Result<crate::midi_note::MIDINoteVec, ParseMIDIHashError> {
+        let mut notes = Vec::new();
+        for track_hash in hash.split('.') {
+            let mut parts = track_hash.split('_');
+            // First part is the note sequence (which may be empty)
+            let sequence_hash = parts.next().unwrap_or("");
+            let track_start = notes.len();
+            if !sequence_hash.is_empty() {
+                for number in sequence_hash.split('-') {
+                    notes.push(decode_note(number)?);
+                }
+            }
+            // Remaining parts are the rhythm, one `<start>-<duration>` pair per note (if not uniform),
+            // followed by the end of the track (if it ends with silence after the last note)
+            let mut rhythm = parts.collect::<Vec<&str>>();
+            let expected = notes.len() - track_start;
+            let has_end = expected > 0
+                && rhythm.len() == expected + 1
+                && rhythm.last().is_some_and(|part| !part.contains('-'));
+            if has_end {
+                let end = rhythm.pop().unwrap_or("");
+                if end.parse::<u32>().is_err() {
+                    return Err(ParseMIDIHashError::InvalidRhythm { input: end.to_string() });
+                }
+            }
+            if !rhythm.is_empty() && rhythm.len() != expected {
+                return Err(ParseMIDIHashError::InvalidRhythmLength { expected, found: rhythm.len() });
+            }
+            for rhythm in rhythm {
+                let valid = match rhythm.split_once('-') {
+                    Some((start, duration)) => start.parse::<u32>().is_ok() && duration.parse::<u32>().is_ok(),
+                    None => false,
+                };
+                if !valid {
+                    return Err(ParseMIDIHashError::InvalidRhythm { input: rhythm.to_string() });
+                }
+            }
+        }
+        Ok(crate::midi_note::MIDINoteVec(notes))
+    }
+
+    /// Generate header chunk (see: [MIDIHeader](struct.MIDIHeader.html))
+    ///
+    /// The number of tracks is capped at 65535, files with more tracks cannot
+    /// be written (see: [write_buffer](#method.write_buffer)).
+    pub fn gen_header(&self) -> MIDIHeader {
+        MIDIHeader::new(
+            vec![0x4d, 0x54, 0x68, 0x64], // 'MThd'
+            0x06,
+            self.format,
+            <u16 as std::convert::TryFrom<usize>>::try_from(self.tracks.len()).unwrap_or(u16::MAX),
+            self.division,
+        )
+    }
+
+    /// Generate the size of this MIDI file in bytes (on disk)
+    pub fn gen_size(&self) -> u32 {
+        14 + self
+            .tracks
+            .iter()
+            .map(|track| 8 + track.gen_size(self.minimal))
+            .sum::<u32>()
+    }
+
+    /// Write MIDI file to buffer
+    ///
+    /// Fails with [InvalidInput](https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput)
+    /// if a format 0 file does not have exactly one track, if the file has more tracks than the
+    /// header chunk can count, or if the notes of a track cannot be written (see: [WriteMIDIFileError](enum.WriteMIDIFileError.html)).
+    pub fn write_buffer<T>(&self, target: &mut T) -> std::io::Result<()>
+    where
+        T: byteorder::WriteBytesExt,
+    {
+        if self.format == MIDIFormat::Format0 && self.tracks.len() != 1 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "format 0 MIDI files must have exactly one track",
+            ));
+        }
+        if self.tracks.len() > u16::MAX as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                WriteMIDIFileError::TooManyTracks { tracks: self.tracks.len() },
+            ));
+        }
+
+        let header = self.gen_header();
+        header.write_buffer(target)?;
+
+        for track in self.tracks.iter() {
+            track.write_buffer(target, self.minimal)?;
+        }
+        Ok(())
+    }
+
+    /// Write MIDI file to path on disk
+    pub fn write_file<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let target_file = std::fs::File::create(path)?;
+        let mut target_file = std::io::BufWriter::new(target_file);
+        self.write_buffer(&mut target_file)?;
+        Ok(())
+    }
+
+    /// Generate buffer containing entire MIDI file
+    pub fn gen_file(&self) -> std::io::Result<Vec<u8>> {
+        let mut buffer = Vec::with_capacity(self.gen_size() as usize);
+        self.write_buffer(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Create `MIDIFile` from chunks of a MIDI file
+    ///
+    /// Each track chunk is converted into a [MIDITrack](struct.MIDITrack.html).  Only
+    /// files created by this library are guaranteed to convert back into an identical `MIDIFile`.
+    /// Files with any track chunk missing the End of Track meta event are considered `minimal`.
+    /// The silence of tracks without notes is read as rests of `division` ticks (as written by
+    /// [new](#method.new)) where possible, so the hash of the file is the same after being
+    /// read back (see: [gen_hash](#method.gen_hash)).
+    pub fn from_chunks(chunks: &MIDIFileChunks) -> Result<MIDIFile, ReadMIDIFileError> {
+        let mut tracks = chunks
+            .tracks
+            .iter()
+            .map(MIDITrack::from_chunk)
+            .collect::<Result<Vec<MIDITrack>, ReadMIDIFileError>>()?;
+        for track in tracks.iter_mut() {
+            split_rests(track, chunks.header.division);
+        }
+        let format = <MIDIFormat as std::convert::TryFrom<u16>>::try_from(chunks.header.format)?;
+        let mut mfile = MIDIFile::from_tracks(tracks, format, chunks.header.division);
+        mfile.minimal = !chunks
+            .tracks
+            .iter()
+            .all(|track| track.events.last().is_some_and(|event| event.is_end_of_track()));
+        Ok(mfile)
+    }
+
+    /// Read MIDI file from buffer
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mfile = libatm::MIDIFile::new(
+    ///     "C:4,CSharp:8,D:5,DSharp:3".parse::<libatm::MIDINoteVec>().unwrap(),
+    ///     libatm::MIDIFormat::Format0,
+    ///     1,
+    /// );
+    /// let buffer = mfile.gen_file().unwrap();
+    /// let observed = libatm::MIDIFile::read_buffer(&mut buffer.as_slice()).unwrap();
+    /// assert_eq!(mfile, observed);
+    /// ```
+    pub fn read_buffer<T>(source: &mut T) -> Result<MIDIFile, ReadMIDIFileError>
+    where
+        T: byteorder::ReadBytesExt,
+    {
+        MIDIFile::from_chunks(&MIDIFileChunks::read_buffer(source)?)
+    }
+
+    /// Read MIDI file from byte slice
+    pub fn from_bytes(bytes: &[u8]) -> Result<MIDIFile, ReadMIDIFileError> {
+        MIDIFile::from_chunks(&MIDIFileChunks::from_bytes(bytes)?)
+    }
+
+    /// Read MIDI file from path on disk
+    pub fn read_file<P: AsRef<std::path::Path>>(path: P) -> Result<MIDIFile, ReadMIDIFileError> {
+        MIDIFile::from_chunks(&MIDIFileChunks::read_file(path)?)
+    }
+}
+
+/// MIDI file template
+///
+/// Generates single track MIDI files (see: [MIDIFile::new](struct.MIDIFile.html#method.new)) for
+/// melodies of a fixed length without allocating.  Melodies of the same length only differ in their
+/// note bytes, so the file is generated once for a placeholder melody, and the note bytes of each
+/// melody are patched into a reusable buffer.  Melodies with rests have a different layout, and are
+/// written with [MIDIFile::write_buffer](struct.MIDIFile.html#method.write_buffer) instead.
+///
+/// # Examples
+///
+/// ```rust
+/// let notes = "C:4,D:4,E:4".parse::<libatm::MIDINoteSet>().unwrap();
+/// let mut melodies = libatm::MIDIMelodyEnumerator::new(&notes, 8);
+/// let mut template = libatm::MIDIFileTemplate::new(8, libatm::MIDIFormat::Format0, 1, false).unwrap();
+/// let size = template.gen_size();
+/// while let Some(melody) = melodies.next_melody() {
+///     let buffer = template.gen_file(melody).unwrap();
+///     // Write buffer to disk, archive, etc.
+///     assert_eq!(size, buffer.len());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MIDIFileTemplate {
+    /// Number of notes in each melody
+    length: usize,
+    format: MIDIFormat,
+    division: u16,
+    minimal: bool,
+    /// File generated for the placeholder melody
+    template: Vec<u8>,
+    /// File generated for the last melody
+    buffer: Vec<u8>,
+    /// Position of the note byte of the `NoteOn` and `NoteOff` event of each note
+    positions: Vec<(usize, usize)>,
+    /// Whether `buffer` has the same layout as `template`
+    patchable: bool,
+}
+
+impl MIDIFileTemplate {
+    /// Create new `MIDIFileTemplate`
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: number of notes in each melody
+    /// * `format`: format of each file
+    /// * `division`: number of ticks each note is played for
+    /// * `minimal`: whether to omit the End of Track meta event (see: [MIDIFile](struct.MIDIFile.html))
+    ///
+    /// Fails if melodies of `length` notes are too long to be written
+    /// (see: [MIDITrack::gen_notes](struct.MIDITrack.html#method.gen_notes)).
+    ///
+    /// # Notes
+    ///
+    /// Panics if `division` is 0.
+    pub fn new(length: usize, format: MIDIFormat, division: u16, minimal: bool) -> Result<Self, WriteMIDIFileError> {
+        // 0 < division
+        assert!(division > 0);
+        let placeholder = crate::midi_note::MIDINote::new(crate::midi_note::MIDINoteType::C, 4);
+        let mut prototype = MIDIFile::new(crate::midi_note::MIDINoteVec(vec![placeholder; length]), format, division);
+        prototype.minimal = minimal;
+        // Generate the events first, so errors are not wrapped in an I/O error
+        let notes = prototype.tracks[0].gen_notes()?;
+        if !minimal {
+            prototype.tracks[0].gen_end_of_track()?;
+        }
+        let mut template = Vec::new();
+        prototype.write_buffer(&mut template)?;
+
+        // Note events start after the header and track chunk header (notes
+        // are released before the next note is pressed, so events alternate)
+        let mut position = 22;
+        let note_positions = notes
+            .iter()
+            .map(|event| {
+                let status_size = match event.status {
+                    0 => 0,
+                    _ => 1,
+                };
+                let note_position = position + crate::midi_event::gen_vlq_size(event.delta_time) as usize + status_size;
+                position += event.gen_size() as usize;
+                note_position
+            })
+            .collect::<Vec<usize>>();
+        let positions = note_positions
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<(usize, usize)>>();
+
+        Ok(Self {
+            length,
+            format,
+            division,
+            minimal,
+            buffer: template.clone(),
+            template,
+            positions,
+            patchable: true,
+        })
+    }
+
+    /// Generate the size in bytes of each file (for melodies without rests)
+    pub fn gen_size(&self) -> usize {
+        self.template.len()
+    }
+
+    /// Generate MIDI file for `melody`
+    ///
+    /// The returned buffer is only valid until the next call, which overwrites it.
+    /// The result is identical to [MIDIFile::gen_file](struct.MIDIFile.html#method.gen_file) for
+    /// a `MIDIFile` created with [MIDIFile::new](struct.MIDIFile.html#method.new).
+    /// Fails if `melody` contains a note outside of the MIDI note range.
+    ///
+    /// # Notes
+    ///
+    /// Panics if `melody` does not have the same length as the template.
+    pub fn gen_file(&mut self, melody: &[crate::midi_note::MIDINote]) -> Result<&[u8], WriteMIDIFileError> {
+        // Melody must have the same length as the template
+        assert_eq!(self.length, melody.len());
+        if melody.iter().any(|note| note.note_type == crate::midi_note::MIDINoteType::Rest) {
+            // Check notes first, so errors are not wrapped in an I/O error (melodies with rests
+            // are never longer than the template, so the notes are the only reason to fail)
+            for note in melody.iter().filter(|note| note.note_type != crate::midi_note::MIDINoteType::Rest) {
+                gen_note_number(note)?;
+            }
+            let mut mfile = MIDIFile::new(crate::midi_note::MIDINoteVec(melody.to_vec()), self.format, self.division);
+            mfile.minimal = self.minimal;
+            self.buffer.clear();
+            self.patchable = false;
+            mfile.write_buffer(&mut self.buffer)?;
+            return Ok(&self.buffer);
+        }
+        if !self.patchable {
+            self.buffer.clear();
+            self.buffer.extend_from_slice(&self.template);
+            self.patchable = true;
+        }
+        for (note, (on_position, off_position)) in melody.iter().zip(self.positions.iter()) {
+            let number = gen_note_number(note)?;
+            self.buffer[*on_position] = number;
+            self.buffer[*off_position] = number;
+        }
+        Ok(&self.buffer)
+    }
+
+    /// Write MIDI file for `melody` to buffer (see: [gen_file](#method.gen_file))
+    ///
+    /// Fails with [InvalidInput](https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput)
+    /// if the file cannot be generated.
+    pub fn write_buffer<T>(&mut self, melody: &[crate::midi_note::MIDINote], target: &mut T) -> std::io::Result<()>
+    where
+        T: std::io::Write,
+    {
+        let buffer = self
+            .gen_file(melody)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        target.write_all(buffer)
+    }
+}
+
+/// Decode a single note of a hash (see: [MIDIFile::decode_hash](struct.MIDIFile.html#method.decode_hash))
+fn decode_note(number: &str) -> Result<crate::midi_note::MIDINote, ParseMIDIHashError> {
+    if number == "r" {
+        return Ok(crate::midi_note::MIDINote::new(crate::midi_note::MIDINoteType::Rest, 0));
+    }
+    number
+        .parse::<u8>()
+        .ok()
+        .and_then(|number| <crate::midi_note::MIDINote as std::convert::TryFrom<u8>>::try_from(number).ok())
+        .ok_or_else(|| ParseMIDIHashError::InvalidNote { input: number.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gen_size_multi_byte_division() {
+        for division in [1, 127, 128, 255, 256, 480, 0x3fff, 0x4000, 0xffff].iter() {
+            let mfile = MIDIFile::new(
+                "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+                MIDIFormat::Format0,
+                *division,
+            );
+            let buffer = mfile.gen_file().unwrap();
+            assert_eq!(mfile.gen_size() as usize, buffer.len());
+            let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+            assert_eq!(mfile.tracks[0].gen_size(false), chunks.tracks[0].header.length);
+            assert_eq!(*division as u32, chunks.tracks[0].events[1].delta_time);
+        }
+    }
+
+    #[test]
+    fn test_write_end_of_track() {
+        let buffer = gen_test_file();
+        assert_eq!(&[0x00, 0xff, 0x2f, 0x00], &buffer[buffer.len() - 4..]);
+        let sequence = "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap();
+        assert_eq!(gen_midi_file_size(&sequence, 1) as usize, buffer.len());
+    }
+
+    #[test]
+    fn test_write_minimal() {
+        let mut mfile = MIDIFile::new(
+            "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        mfile.minimal = true;
+        let buffer = mfile.gen_file().unwrap();
+        let expected = vec![
+            0x4d, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01,
+            0x4d, 0x54, 0x72, 0x6b, 0x00, 0x00, 0x00, 0x13,
+            0x00, 0x90, 0x3c, 0x64, 0x01, 0x3c, 0x00,
+            0x00, 0x3e, 0x64, 0x01, 0x3e, 0x00,
+            0x00, 0x40, 0x64, 0x01, 0x40, 0x00,
+        ];
+        assert_eq!(expected, buffer);
+        assert_eq!(gen_minimal_midi_file_size(&mfile.tracks[0].gen_sequence(), 1) as usize, buffer.len());
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_write_meta_events() {
+        let mut mfile = MIDIFile::new(
+            "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        mfile.tracks[0].meta_events = vec![
+            MIDIMetaEvent::TrackName("Melody".to_string()),
+            MIDIMetaEvent::from_bpm(90),
+            MIDIMetaEvent::TimeSignature { numerator: 3, denominator: 2, clocks_per_click: 24, notated_32nd_notes: 8 },
+            MIDIMetaEvent::KeySignature { sharps_flats: 2, minor: false },
+        ];
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+        assert_eq!(MIDIEvent::Meta(MIDIMetaEvent::Tempo(666_666)), chunks.tracks[0].events[1].event);
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_write_channel_messages() {
+        let mut mfile = MIDIFile::new(
+            "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        mfile.tracks[0].meta_events = vec![MIDIMetaEvent::TrackName("Guitar".to_string())];
+        mfile.tracks[0].channel_messages = vec![
+            MIDIChannelMessage::ProgramChange { channel: 0, program: 24 },
+            MIDIChannelMessage::ControlChange { channel: 0, controller: 11, value: 90 },
+            MIDIChannelMessage::PitchWheelChange { channel: 0, value: 0x2100 },
+        ];
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+        // First note follows the meta event and three channel messages
+        assert_eq!(
+            MIDIEvent::Channel(MIDIChannelMessage::NoteOn { channel: 0, note: 60, velocity: 0x64 }),
+            chunks.tracks[0].events[4].event,
+        );
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    fn gen_test_multi_track_file() -> MIDIFile {
+        let mut tempo_track = MIDITrack::from_notes(Vec::new());
+        tempo_track.meta_events = vec![
+            MIDIMetaEvent::from_bpm(140),
+            MIDIMetaEvent::TimeSignature { numerator: 4, denominator: 2, clocks_per_click: 24, notated_32nd_notes: 8 },
+        ];
+        let mut melody_track = MIDITrack::new("C:5,D:5,E:5,F:5".parse::<crate::midi_note::MIDINoteVec>().unwrap(), 2);
+        melody_track.meta_events = vec![MIDIMetaEvent::TrackName("Melody".to_string())];
+        let mut bass_track = MIDITrack::new("C:2,G:2".parse::<crate::midi_note::MIDINoteVec>().unwrap(), 2);
+        bass_track.channel = 1;
+        bass_track.channel_messages = vec![MIDIChannelMessage::ProgramChange { channel: 1, program: 33 }];
+        MIDIFile::from_tracks(vec![tempo_track, melody_track, bass_track], MIDIFormat::Format1, 2)
+    }
+
+    #[test]
+    fn test_write_multi_track() {
+        let mfile = gen_test_multi_track_file();
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+        assert_eq!(3, chunks.header.tracks);
+        assert_eq!(1, chunks.header.format);
+        for (track, chunk) in mfile.tracks.iter().zip(chunks.tracks.iter()) {
+            assert_eq!(track.gen_size(false), chunk.header.length);
+            assert!(chunk.events.last().unwrap().is_end_of_track());
+        }
+        assert_eq!(
+            MIDIEvent::Channel(MIDIChannelMessage::NoteOn { channel: 1, note: 36, velocity: 0x64 }),
+            chunks.tracks[2].events[1].event,
+        );
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_write_empty_track() {
+        let mfile = MIDIFile::new(crate::midi_note::MIDINoteVec(Vec::new()), MIDIFormat::Format0, 1);
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(26, buffer.len());
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_write_format0_multi_track() {
+        let mut mfile = gen_test_multi_track_file();
+        mfile.format = MIDIFormat::Format0;
+        let observed = mfile.gen_file().map_err(|err| err.kind());
+        assert_eq!(Err(std::io::ErrorKind::InvalidInput), observed);
+    }
+
+    #[test]
+    fn test_write_too_many_tracks() {
+        let tracks = vec![MIDITrack::from_notes(Vec::new()); 0x10000];
+        let mfile = MIDIFile::from_tracks(tracks, MIDIFormat::Format1, 1);
+        assert_eq!(u16::MAX, mfile.gen_header().tracks);
+        let err = mfile.write_buffer(&mut std::io::sink()).unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+        assert!(matches!(
+            err.get_ref().and_then(|err| err.downcast_ref::<WriteMIDIFileError>()),
+            Some(WriteMIDIFileError::TooManyTracks { tracks: 0x10000 })
+        ));
+    }
+
+    #[test]
+    fn test_write_patterns() {
+        let patterns = vec![("C:4,D:4,E:4", 120), ("G:3,G:3", 90), ("A:4", 60)]
+            .into_iter()
+            .map(|(melody, bpm)| {
+                let mut pattern = MIDITrack::new(melody.parse::<crate::midi_note::MIDINoteVec>().unwrap(), 1);
+                pattern.meta_events = vec![
+                    MIDIMetaEvent::from_bpm(bpm),
+                    MIDIMetaEvent::TimeSignature { numerator: 3, denominator: 2, clocks_per_click: 24, notated_32nd_notes: 8 },
+                ];
+                pattern
+            })
+            .collect::<Vec<MIDITrack>>();
+        let mfile = MIDIFile::from_patterns(patterns, 1).unwrap();
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+        assert_eq!(2, chunks.header.format);
+        assert_eq!(3, chunks.header.tracks);
+        for (idx, (chunk, bpm)) in chunks.tracks.iter().zip(vec![120, 90, 60]).enumerate() {
+            assert_eq!(MIDIEvent::Meta(MIDIMetaEvent::SequenceNumber(idx as u16)), chunk.events[0].event);
+            assert_eq!(MIDIEvent::Meta(MIDIMetaEvent::from_bpm(bpm)), chunk.events[1].event);
+        }
+
+        let observed = MIDIFile::from_bytes(&buffer).unwrap();
+        assert_eq!(mfile, observed);
+        let split = observed.split_patterns();
+        assert_eq!(
+            vec!["60-62-64", "55-55", "69"],
+            split.iter().map(|pattern| pattern.gen_hash().unwrap()).collect::<Vec<String>>(),
+        );
+        assert!(split.iter().all(|pattern| pattern.format == MIDIFormat::Format0));
+        assert_eq!(split[1].tracks[0], mfile.tracks[1]);
+    }
+
+    #[test]
+    fn test_write_patterns_keeps_sequence_number() {
+        let mut pattern = MIDITrack::new("C:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(), 1);
+        pattern.meta_events = vec![MIDIMetaEvent::SequenceNumber(42)];
+        let mfile = MIDIFile::from_patterns(vec![pattern.clone()], 1).unwrap();
+        assert_eq!(vec![pattern], mfile.tracks);
+    }
+
+    #[test]
+    fn test_write_patterns_too_many() {
+        let patterns = vec![MIDITrack::from_notes(Vec::new()); 0x10000];
+        assert!(matches!(
+            MIDIFile::from_patterns(patterns, 1),
+            Err(WriteMIDIFileError::TooManyTracks { tracks: 0x10000 })
+        ));
+        let patterns = vec![MIDITrack::from_notes(Vec::new()); 0xffff];
+        let mfile = MIDIFile::from_patterns(patterns, 1).unwrap();
+        assert_eq!(vec![MIDIMetaEvent::SequenceNumber(0xfffe)], mfile.tracks[0xfffe].meta_events);
+    }
+
+    fn gen_test_note(note: &str, start: u32, duration: u32) -> crate::midi_note::MIDITimedNote {
+        crate::midi_note::MIDITimedNote::new(note.parse::<crate::midi_note::MIDINote>().unwrap(), start, duration)
+    }
+
+    #[test]
+    fn test_write_sequence_matches_minimal_layout() {
+        // Sequential notes must produce the same events as earlier (monophonic) versions
+        let mut mfile = MIDIFile::new(
+            "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        mfile.minimal = true;
+        let sequence = mfile.tracks[0].gen_sequence();
+        assert_eq!(gen_minimal_midi_file_size(&sequence, 1), mfile.gen_size());
+        assert_eq!(gen_minimal_midi_track_size(&sequence, 1), mfile.tracks[0].gen_size(true));
+        let buffer = mfile.gen_file().unwrap();
+        let expected = vec![
+            0x00, 0x90, 0x3c, 0x64, 0x01, 0x3c, 0x00,
+            0x00, 0x3e, 0x64, 0x01, 0x3e, 0x00,
+            0x00, 0x40, 0x64, 0x01, 0x40, 0x00,
+        ];
+        assert_eq!(expected, buffer[22..].to_vec());
+
+        // Multi-byte delta times
+        mfile.division = 200;
+        mfile.tracks[0] = MIDITrack::new("C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(), 200);
+        assert_eq!(gen_minimal_midi_file_size(&sequence, 200), mfile.gen_size());
+        let buffer = mfile.gen_file().unwrap();
+        let expected = vec![
+            0x00, 0x90, 0x3c, 0x64, 0x81, 0x48, 0x3c, 0x00,
+            0x00, 0x3e, 0x64, 0x81, 0x48, 0x3e, 0x00,
+            0x00, 0x40, 0x64, 0x81, 0x48, 0x40, 0x00,
+        ];
+        assert_eq!(expected, buffer[22..].to_vec());
+    }
+
+    #[test]
+    fn test_write_tick_overflow() {
+        let track = MIDITrack::from_notes(vec![gen_test_note("C:4", u32::MAX - 1, 2)]);
+        assert!(matches!(track.gen_notes(), Err(WriteMIDIFileError::TickOverflow)));
+        let mfile = MIDIFile::from_tracks(vec![track], MIDIFormat::Format0, 1);
+        let err = mfile.gen_file().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+        let rest = MIDITrack::from_notes(vec![gen_test_note("Rest:4", u32::MAX, 1)]);
+        assert!(matches!(rest.gen_end_of_track(), Err(WriteMIDIFileError::TickOverflow)));
+    }
+
+    #[test]
+    fn test_write_delta_time_overflow() {
+        let delta_time = crate::midi_event::MAX_VLQ + 1;
+        let track = MIDITrack::from_notes(vec![gen_test_note("C:4", delta_time, 1)]);
+        assert!(matches!(track.gen_notes(), Err(WriteMIDIFileError::DeltaTimeOverflow { delta_time: found }) if found == delta_time));
+        let track = MIDITrack::from_notes(vec![gen_test_note("C:4", 0, 1), gen_test_note("Rest:4", 1, delta_time)]);
+        assert!(matches!(track.gen_end_of_track(), Err(WriteMIDIFileError::DeltaTimeOverflow { delta_time: found }) if found == delta_time));
+        assert!(track.write_buffer(&mut Vec::new(), true).is_ok());
+        assert!(track.write_buffer(&mut Vec::new(), false).is_err());
+    }
+
+    #[test]
+    fn test_read_tick_overflow() {
+        let event = MIDITrackEvent {
+            delta_time: crate::midi_event::MAX_VLQ,
+            event: MIDIEvent::Meta(MIDIMetaEvent::Text(String::new())),
+        };
+        let mut chunk = MIDITrackChunk {
+            header: MIDITrackHeader::new(b"MTrk".to_vec(), 0),
+            events: vec![event; 16],
+        };
+        assert!(MIDITrack::from_chunk(&chunk).is_ok());
+        chunk.events.push(chunk.events[0].clone());
+        assert!(matches!(MIDITrack::from_chunk(&chunk), Err(ReadMIDIFileError::TickOverflow)));
+    }
+
+    #[test]
+    fn test_read_mixed_channels() {
+        let gen_event = |delta_time: u32, message: MIDIChannelMessage| MIDITrackEvent {
+            delta_time,
+            event: MIDIEvent::Channel(message),
+        };
+        let mut chunk = MIDITrackChunk {
+            header: MIDITrackHeader::new(b"MTrk".to_vec(), 0),
+            events: vec![
+                gen_event(0, MIDIChannelMessage::NoteOn { channel: 2, note: 60, velocity: 0x64 }),
+                // Stray release on another channel does not release the note
+                gen_event(1, MIDIChannelMessage::NoteOff { channel: 3, note: 60, velocity: 0x40 }),
+                gen_event(1, MIDIChannelMessage::NoteOff { channel: 2, note: 60, velocity: 0x40 }),
+            ],
+        };
+        let track = MIDITrack::from_chunk(&chunk).unwrap();
+        assert_eq!(2, track.channel);
+        assert_eq!(vec![gen_test_note("C:4", 0, 2)], track.notes);
+        chunk.events.push(gen_event(0, MIDIChannelMessage::NoteOn { channel: 3, note: 64, velocity: 0x64 }));
+        assert!(matches!(
+            MIDITrack::from_chunk(&chunk),
+            Err(ReadMIDIFileError::MixedChannels { first: 2, second: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_write_chords() {
+        let chords = vec!["C:4,E:4,G:4", "D:4,F:4"]
+            .into_iter()
+            .map(|chord| chord.parse::<crate::midi_note::MIDINoteVec>().unwrap())
+            .collect::<Vec<crate::midi_note::MIDINoteVec>>();
+        let track = MIDITrack::from_chords(chords, 2);
+        let observed = track
+            .gen_notes()
+            .unwrap()
+            .iter()
+            .map(|event| (event.delta_time, event.status, event.note, event.velocity))
+            .collect::<Vec<(u32, u8, u8, u8)>>();
+        let expected = vec![
+            (0, 0x90, 60, 0x64),
+            (0, 0, 64, 0x64),
+            (0, 0, 67, 0x64),
+            (2, 0, 60, 0),
+            (0, 0, 64, 0),
+            (0, 0, 67, 0),
+            (0, 0, 62, 0x64),
+            (0, 0, 65, 0x64),
+            (2, 0, 62, 0),
+            (0, 0, 65, 0),
+        ];
+        assert_eq!(expected, observed);
+    }
+
+    #[test]
+    fn test_write_overlapping_notes() {
+        let track = MIDITrack::from_notes(vec![
+            gen_test_note("C:4", 0, 4),
+            gen_test_note("E:4", 2, 4),
+            gen_test_note("G:4", 3, 0),
+        ]);
+        let observed = track
+            .gen_notes()
+            .unwrap()
+            .iter()
+            .map(|event| (event.delta_time, event.note, event.velocity))
+            .collect::<Vec<(u32, u8, u8)>>();
+        let expected = vec![
+            (0, 60, 0x64),
+            (2, 64, 0x64),
+            (1, 67, 0x64),
+            (0, 67, 0),
+            (1, 60, 0),
+            (2, 64, 0),
+        ];
+        assert_eq!(expected, observed);
+        for minimal in [false, true].iter() {
+            let mut buffer = Vec::new();
+            track.write_buffer(&mut buffer, *minimal).unwrap();
+            assert_eq!(track.gen_size(*minimal) as usize + 8, buffer.len());
+        }
+    }
+
+    #[test]
+    fn test_read_overlapping_notes() {
+        let track = MIDITrack::from_notes(vec![
+            gen_test_note("C:4", 0, 4),
+            gen_test_note("C:4", 2, 4),
+            gen_test_note("E:4", 2, 1),
+            gen_test_note("G:4", 7, 0),
+        ]);
+        let mfile = MIDIFile::from_tracks(vec![track], MIDIFormat::Format0, 1);
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_write_rhythm() {
+        let notes = "C:4/1,F:4@0/20000,D:4@1/200,E:4/1".parse::<crate::midi_note::MIDITimedNoteVec>().unwrap();
+        let mfile = MIDIFile::from_timed_notes(notes, MIDIFormat::Format0, 480);
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_gen_hash_rhythm() {
+        let sequence = "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap();
+        let uniform = MIDIFile::new(sequence.clone(), MIDIFormat::Format0, 4);
+        assert_eq!("60-62-64", uniform.gen_hash().unwrap());
+        let rhythm = MIDIFile::from_timed_notes(
+            crate::midi_note::MIDITimedNoteVec::from_rhythm(&sequence, &[4, 4, 8]),
+            MIDIFormat::Format0,
+            4,
+        );
+        assert_eq!("60-62-64_0-4_4-4_8-8", rhythm.gen_hash().unwrap());
+        assert_eq!("606264", rhythm.gen_legacy_hash().unwrap());
+    }
+
+    #[test]
+    fn test_write_rests() {
+        let mfile = MIDIFile::new(
+            "C:4,Rest:4,Rest:4,D:4,Rest:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        let expected_notes = vec![
+            0x00, 0x90, 0x3c, 0x64,
+            0x01, 0x3c, 0x00,
+            0x02, 0x3e, 0x64,
+            0x01, 0x3e, 0x00,
+        ];
+        let mut buffer = Vec::new();
+        for event in mfile.tracks[0].gen_notes().unwrap().iter() {
+            event.write_buffer(&mut buffer).unwrap();
+        }
+        assert_eq!(expected_notes, buffer);
+
+        // End of Track is delayed by the trailing rest
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(vec![0x01, 0xff, 0x2f, 0x00], buffer[buffer.len() - 4..].to_vec());
+        assert_eq!(mfile.gen_size() as usize, buffer.len());
+        let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+        assert_eq!(5, chunks.tracks[0].events.len());
+        assert!(chunks.tracks[0].events.iter().all(|event| match event.event {
+            MIDIEvent::Channel(message) => message.data().0 < 0x80,
+            _ => true,
+        }));
+        // Written and read back tracks have the same hash
+        let observed = MIDIFile::from_bytes(&buffer).unwrap();
+        assert_eq!("60-r-r-62-r", mfile.gen_hash().unwrap());
+        assert_eq!(mfile.gen_hash().unwrap(), observed.gen_hash().unwrap());
+        assert_eq!(buffer, observed.gen_file().unwrap());
+
+        let minimal = MIDIFile { minimal: true, ..mfile };
+        assert_eq!(minimal.gen_size() as usize, minimal.gen_file().unwrap().len());
+    }
+
+    #[test]
+    fn test_gen_hash_rests_round_trip() {
+        let cases = vec![
+            ("Rest:0,C:4,Rest:0", 1, "r-60-r"),
+            ("Rest:0,Rest:0", 1, "r-r"),
+            ("Rest:0", 2, "r"),
+            ("C:4,D:4", 1, "60-62"),
+        ];
+        for (sequence, division, expected) in cases.into_iter() {
+            let sequence = sequence.parse::<crate::midi_note::MIDINoteVec>().unwrap();
+            let mfile = MIDIFile::new(sequence.clone(), MIDIFormat::Format0, division);
+            let observed = MIDIFile::from_bytes(&mfile.gen_file().unwrap()).unwrap();
+            assert_eq!(expected, mfile.gen_hash().unwrap());
+            assert_eq!(expected, observed.gen_hash().unwrap());
+            assert_eq!(sequence, MIDIFile::decode_hash(&mfile.gen_hash().unwrap()).unwrap());
+        }
+        // Rests of 0 ticks are not written, but are still encoded
+        let sequence = "Rest:0,Rest:0".parse::<crate::midi_note::MIDINoteVec>().unwrap();
+        let mfile = MIDIFile::new(sequence.clone(), MIDIFormat::Format0, 0);
+        assert_eq!("r-r", mfile.gen_hash().unwrap());
+        assert_eq!(sequence, MIDIFile::decode_hash(&mfile.gen_hash().unwrap()).unwrap());
+        // Silence is only encoded after the last note
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidRhythmLength { expected: 0, found: 1 }),
+            MIDIFile::decode_hash("_2"),
+        );
+
+        // Rhythm followed by silence
+        let notes = vec![gen_test_note("C:4", 0, 2), gen_test_note("D:4", 2, 1), gen_test_note("Rest:4", 3, 2)];
+        let mfile = MIDIFile::from_tracks(vec![MIDITrack::from_notes(notes)], MIDIFormat::Format0, 1);
+        let observed = MIDIFile::from_bytes(&mfile.gen_file().unwrap()).unwrap();
+        assert_eq!("60-62_0-2_2-1_5", mfile.gen_hash().unwrap());
+        assert_eq!(mfile.gen_hash().unwrap(), observed.gen_hash().unwrap());
+        assert_eq!(
+            "C:4,D:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFile::decode_hash(&mfile.gen_hash().unwrap()).unwrap(),
+        );
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidRhythm { input: String::from("x") }),
+            MIDIFile::decode_hash("60-62_0-2_2-1_x"),
+        );
+
+        // Long silences are not written out as rests
+        let notes = vec![gen_test_note("C:4", 0, 1), gen_test_note("Rest:4", 1, u32::MAX - 1)];
+        let track = MIDITrack::from_notes(notes);
+        assert_eq!(format!("60_0-1_{}", u32::MAX), track.gen_hash().unwrap());
+    }
+
+    #[test]
+    fn test_gen_hash_only_rests() {
+        let gen_test_file = |sequence: &str, division: u16| MIDIFile::new(
+            sequence.parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            division,
+        );
+        // Both tracks end after 2 ticks
+        let longer = gen_test_file("Rest:4,Rest:4", 1);
+        let shorter = gen_test_file("Rest:4", 2);
+        assert_eq!("r-r", longer.gen_hash().unwrap());
+        assert_eq!("r", shorter.gen_hash().unwrap());
+        for mfile in [longer, shorter].iter() {
+            let observed = MIDIFile::from_bytes(&mfile.gen_file().unwrap()).unwrap();
+            assert_eq!(mfile.gen_hash().unwrap(), observed.gen_hash().unwrap());
+        }
+
+        // Rests that are not uniform keep their rhythm
+        let track = MIDITrack::from_notes(vec![gen_test_note("Rest:4", 0, 1), gen_test_note("Rest:4", 1, 2)]);
+        assert_eq!("r-r_0-1_1-2", track.gen_hash().unwrap());
+        assert_eq!("", MIDITrack::from_notes(Vec::new()).gen_hash().unwrap());
+    }
+
+    #[test]
+    fn test_gen_midi_track_size_rests() {
+        let sequences = vec!["C:4,Rest:4,Rest:4,D:4,Rest:4", "Rest:4,C:4", "Rest:4,Rest:4", "", "C:4"];
+        for sequence in sequences.into_iter() {
+            let sequence = match sequence {
+                "" => crate::midi_note::MIDINoteVec(Vec::new()),
+                _ => sequence.parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            };
+            for division in [1, 100, 0x4000].iter() {
+                let mut mfile = MIDIFile::new(sequence.clone(), MIDIFormat::Format0, *division);
+                assert_eq!(gen_midi_file_size(&sequence, *division) as usize, mfile.gen_file().unwrap().len());
+                assert_eq!(gen_midi_track_size(&sequence, *division), mfile.tracks[0].gen_size(false));
+                mfile.minimal = true;
+                assert_eq!(gen_minimal_midi_file_size(&sequence, *division) as usize, mfile.gen_file().unwrap().len());
+                assert_eq!(gen_minimal_midi_track_size(&sequence, *division), mfile.tracks[0].gen_size(true));
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_lowest_octave() {
+        let mfile = MIDIFile::new(
+            "C:-1,B:-1,G:9".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        assert_eq!("0-11-127", mfile.gen_hash().unwrap());
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_template_matches_gen_file() {
+        let notes = "C:-1,C:4,G:9".parse::<crate::midi_note::MIDINoteSet>().unwrap();
+        for (division, minimal) in vec![(1, false), (1, true), (480, false), (0x7fff, true)].into_iter() {
+            let mut template = MIDIFileTemplate::new(4, MIDIFormat::Format0, division, minimal).unwrap();
+            for melody in crate::midi_enumeration::MIDIMelodyEnumerator::new(&notes, 4) {
+                let mut mfile = MIDIFile::new(melody.clone(), MIDIFormat::Format0, division);
+                mfile.minimal = minimal;
+                assert_eq!(mfile.gen_file().unwrap(), template.gen_file(&melody).unwrap());
+            }
+        }
+        let mut template = MIDIFileTemplate::new(0, MIDIFormat::Format1, 1, false).unwrap();
+        let mfile = MIDIFile::new(crate::midi_note::MIDINoteVec(Vec::new()), MIDIFormat::Format1, 1);
+        assert_eq!(mfile.gen_file().unwrap(), template.gen_file(&[]).unwrap());
+    }
+
+    #[test]
+    fn test_template_rests() {
+        let mut template = MIDIFileTemplate::new(3, MIDIFormat::Format0, 1, false).unwrap();
+        let cases = vec!["C:4,D:4,E:4", "C:4,Rest:4,E:4", "Rest:4,Rest:4,Rest:4", "E:4,D:4,C:4"];
+        for melody in cases.into_iter() {
+            let melody = melody.parse::<crate::midi_note::MIDINoteVec>().unwrap();
+            let mfile = MIDIFile::new(melody.clone(), MIDIFormat::Format0, 1);
+            let mut buffer = Vec::new();
+            template.write_buffer(&melody, &mut buffer).unwrap();
+            assert_eq!(mfile.gen_file().unwrap(), buffer);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_template_wrong_length() {
+        let mut template = MIDIFileTemplate::new(3, MIDIFormat::Format0, 1, false).unwrap();
+        let _ = template.gen_file(&"C:4,D:4".parse::<crate::midi_note::MIDINoteVec>().unwrap());
+    }
+
+    #[test]
+    fn test_template_invalid_note() {
+        let mut template = MIDIFileTemplate::new(2, MIDIFormat::Format0, 1, false).unwrap();
+        // Notes outside of the MIDI note range can only be created by setting the fields directly
+        let invalid = crate::midi_note::MIDINote { note_type: crate::midi_note::MIDINoteType::A, octave: 10 };
+        let rest = crate::midi_note::MIDINote::new(crate::midi_note::MIDINoteType::Rest, 4);
+        let valid = "C:4,D:4".parse::<crate::midi_note::MIDINoteVec>().unwrap();
+        for melody in vec![vec![valid[0], invalid], vec![rest, invalid]].into_iter() {
+            assert!(matches!(template.gen_file(&melody), Err(WriteMIDIFileError::InvalidNote(_))));
+            let err = template.write_buffer(&melody, &mut Vec::new()).unwrap_err();
+            assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+            // Template can still be used after an error
+            let mfile = MIDIFile::new(valid.clone(), MIDIFormat::Format0, 1);
+            assert_eq!(mfile.gen_file().unwrap(), template.gen_file(&valid).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_template_too_long() {
+        assert!(MIDIFileTemplate::new(0x10001, MIDIFormat::Format0, 0xffff, true).is_ok());
+        assert!(matches!(
+            MIDIFileTemplate::new(0x10002, MIDIFormat::Format0, 0xffff, true),
+            Err(WriteMIDIFileError::TickOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_gen_legacy_hash() {
+        // Output of earlier versions of this library
+        let cases = vec![
+            ("C:4,CSharp:8,D:5,DSharp:3", 1, "601097451"),
+            ("C:4,Rest:4,Rest:4,D:4,Rest:4", 1, "6042949672954294967295624294967295"),
+            ("Rest:4,Rest:4", 1, "42949672954294967295"),
+            ("Rest:4,C:-1", 0, "42949672950"),
+        ];
+        for (sequence, division, expected) in cases.into_iter() {
+            let mfile = MIDIFile::new(
+                sequence.parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+                MIDIFormat::Format0,
+                division,
+            );
+            assert_eq!(expected, mfile.gen_legacy_hash().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_gen_hash_collision() {
+        let first = MIDIFile::new(
+            "C:0,E:8".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        let second = MIDIFile::new(
+            "CSharp:9,C:0".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        assert_eq!(first.gen_legacy_hash().unwrap(), second.gen_legacy_hash().unwrap());
+        assert_eq!("12-112", first.gen_hash().unwrap());
+        assert_eq!("121-12", second.gen_hash().unwrap());
+
+        // Notes outside of the MIDI note range can only be created by setting the fields directly
+        let invalid = crate::midi_note::MIDINote { note_type: crate::midi_note::MIDINoteType::A, octave: 9 };
+        let expected = || crate::midi_note::MIDINoteRangeError::OutOfRange {
+            note_type: crate::midi_note::MIDINoteType::A,
+            octave: 9,
+        };
+        let mfile = MIDIFile::new(crate::midi_note::MIDINoteVec(vec![invalid]), MIDIFormat::Format0, 1);
+        assert_eq!(Err(expected()), mfile.gen_hash());
+        assert_eq!(Err(expected()), mfile.gen_legacy_hash());
+        assert!(matches!(mfile.tracks[0].gen_notes(), Err(WriteMIDIFileError::InvalidNote(err)) if err == expected()));
+        assert_eq!(std::io::ErrorKind::InvalidInput, mfile.gen_file().unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_decode_hash() {
+        let mfile = MIDIFile::from_tracks(
+            vec![
+                MIDITrack::new("C:4,Rest:0,D:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(), 1),
+                MIDITrack::from_notes(Vec::new()),
+                MIDITrack::from_notes(vec![gen_test_note("A:4", 0, 2), gen_test_note("B:4", 2, 1)]),
+            ],
+            MIDIFormat::Format1,
+            1,
+        );
+        assert_eq!("60-r-62..69-71_0-2_2-1", mfile.gen_hash().unwrap());
+        assert_eq!(
+            "C:4,Rest:0,D:4,A:4,B:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFile::decode_hash(&mfile.gen_hash().unwrap()).unwrap(),
+        );
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidNote { input: String::from("128") }),
+            MIDIFile::decode_hash("60-128"),
+        );
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidNote { input: String::from("60x") }),
+            MIDIFile::decode_hash("60x"),
+        );
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidRhythm { input: String::from("0") }),
+            MIDIFile::decode_hash("60-62_0_1-1"),
+        );
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidRhythmLength { expected: 2, found: 1 }),
+            MIDIFile::decode_hash("60-62_0-1"),
+        );
+        // Rhythm is counted per track
+        assert_eq!(
+            Err(ParseMIDIHashError::InvalidRhythmLength { expected: 1, found: 2 }),
+            MIDIFile::decode_hash("60-62_0-1_1-1.64_0-1_1-1"),
+        );
+    }
+
+    #[test]
+    fn test_write_velocity() {
+        let mut mfile = MIDIFile::new(
+            "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        mfile.set_velocity(0x50);
+        mfile.tracks[0].notes[1].velocity = Some(0x7f);
+        let velocities = mfile.tracks[0]
+            .gen_notes()
+            .unwrap()
+            .iter()
+            .map(|event| event.velocity)
+            .collect::<Vec<u8>>();
+        assert_eq!(vec![0x50, 0, 0x7f, 0, 0x50, 0], velocities);
+        let buffer = mfile.gen_file().unwrap();
+        assert_eq!(mfile, MIDIFile::from_bytes(&buffer).unwrap());
+    }
+
+    #[test]
+    fn test_write_velocity_curve_round_trip() {
+        let mut mfile = MIDIFile::new(
+            "C:4,D:4,E:4,F:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        );
+        mfile.tracks[0].velocity_curve = Some(MIDIVelocityCurve::Linear { from: 0x70, to: 0x10 });
+        assert_eq!(vec![0x70, 0x50, 0x30, 0x10], mfile.tracks[0].gen_velocities());
+        // Curve is not preserved, but the velocity of every note is
+        let buffer = mfile.gen_file().unwrap();
+        let observed = MIDIFile::from_bytes(&buffer).unwrap();
+        assert_eq!(0x70, observed.tracks[0].velocity);
+        assert_eq!(
+            vec![None, Some(0x50), Some(0x30), Some(0x10)],
+            observed.tracks[0].notes.iter().map(|note| note.velocity).collect::<Vec<Option<u8>>>(),
+        );
+        assert_eq!(buffer, observed.gen_file().unwrap());
+    }
+
+    #[test]
+    fn test_gen_velocities_chord() {
+        let mut track = MIDITrack::from_notes(vec![
+            gen_test_note("C:4", 2, 1),
+            gen_test_note("E:4", 0, 1),
+            gen_test_note("G:4", 0, 1),
+        ]);
+        track.velocity_curve = Some(MIDIVelocityCurve::Cycle(vec![1, 2, 3]));
+        assert_eq!(vec![3, 1, 2], track.gen_velocities());
+        track.velocity_curve = Some(MIDIVelocityCurve::Linear { from: 0x10, to: 0x20 });
+        assert_eq!(vec![0x20, 0x10, 0x10], track.gen_velocities());
+    }
+
+    #[test]
+    fn test_gen_velocities_clamped() {
+        let mut track = MIDITrack::new("C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(), 1);
+        track.velocity = 0;
+        assert_eq!(vec![1, 1, 1], track.gen_velocities());
+        track.velocity_curve = Some(MIDIVelocityCurve::Linear { from: 0, to: 0xff });
+        track.notes[1].velocity = Some(0);
+        assert_eq!(vec![1, 1, 0x7f], track.gen_velocities());
+        track.notes[1].velocity = Some(0x90);
+        assert_eq!(vec![1, 0x7f, 0x7f], track.gen_velocities());
+        // Every note is pressed (a velocity of 0 would release it) and none panic
+        let mfile = MIDIFile::from_tracks(vec![track.clone()], MIDIFormat::Format0, 1);
+        let observed = MIDIFile::from_bytes(&mfile.gen_file().unwrap()).unwrap();
+        assert_eq!(track.notes.len(), observed.tracks[0].notes.len());
+        assert!(observed.tracks[0].notes.iter().all(|note| note.duration == 1));
+    }
+
+    fn gen_test_file() -> Vec<u8> {
+        MIDIFile::new(
+            "C:4,D:4,E:4".parse::<crate::midi_note::MIDINoteVec>().unwrap(),
+            MIDIFormat::Format0,
+            1,
+        ).gen_file().unwrap()
+    }
+
+    #[test]
+    fn test_read_header() {
+        let buffer = gen_test_file();
+        let observed = MIDIHeader::read_buffer(&mut buffer.as_slice()).unwrap();
+        let expected = MIDIHeader::new(b"MThd".to_vec(), 6, MIDIFormat::Format0, 1, 1);
+        assert_eq!(expected, observed);
+    }
+
+    #[test]
+    fn test_read_header_invalid_chunk_type() {
+        let mut buffer = gen_test_file();
+        buffer[0] = b'X';
+        assert!(matches!(
+            MIDIHeader::read_buffer(&mut buffer.as_slice()),
+            Err(ReadMIDIFileError::InvalidChunkType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_header_invalid_format() {
+        let mut buffer = gen_test_file();
+        buffer[9] = 3;
+        assert!(matches!(
+            MIDIHeader::read_buffer(&mut buffer.as_slice()),
+            Err(ReadMIDIFileError::InvalidFormat(3))
+        ));
+    }
+
+    #[test]
+    fn test_read_chunks_skips_unknown_chunk() {
+        let mut buffer = gen_test_file();
+        let mut alien_chunk = vec![b'X', b'Y', b'Z', b'W', 0, 0, 0, 2, 0xaa, 0xbb];
+        let mut track_chunk = buffer.split_off(14);
+        buffer.append(&mut alien_chunk);
+        buffer.append(&mut track_chunk);
+        let chunks = MIDIFileChunks::from_bytes(&buffer).unwrap();
+        assert_eq!(1, chunks.tracks.len());
+        assert_eq!(
+            MIDIEvent::Channel(MIDIChannelMessage::NoteOn { channel: 0, note: 60, velocity: 0x64 }),
+            chunks.tracks[0].events[0].event,
+        );
+    }
+
+    #[test]
+    fn test_read_chunks_truncated_track() {
+        let mut buffer = gen_test_file();
+        buffer.pop();
+        assert!(matches!(
+            MIDIFileChunks::from_bytes(&buffer),
+            Err(ReadMIDIFileError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_chunks_truncated_track_length() {
+        let mut buffer = gen_test_file();
+        // Track chunk claims 4 GiB of events
+        buffer[18..22].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        assert!(matches!(
+            MIDIFileChunks::from_bytes(&buffer),
+            Err(ReadMIDIFileError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_chunks_invalid_event() {
+        let mut buffer = gen_test_file();
+        // Replace NoteOn status byte with data byte (no running status)
+        buffer[23] = 0x3c;
+        assert!(matches!(
+            MIDIFileChunks::from_bytes(&buffer),
+            Err(ReadMIDIFileError::InvalidEvent { track: 0, event: 0, .. })
+        ));
     }
 }
@@ -18,7 +18,9 @@ pub enum ParseMIDINoteTypeError {
 /// Represents each note in an octave, where each "*Sharp" value
 /// is an enharmonic key.  Each note type must be combined with an
 /// integer value to fully represent a key on the piano (see: [MIDINote](struct.MIDINote.html)).
-/// The `Rest` note type represents silence, or the absence of a note.
+/// The `Rest` note type represents silence, or the absence of a note.  Any spelling
+/// (including double sharps and flats) is accepted when parsing, but the spelling itself
+/// is not kept (see: [SpelledPitch](struct.SpelledPitch.html)).
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum MIDINoteType {
     C,
@@ -36,7 +38,33 @@ pub enum MIDINoteType {
     Rest,
 }
 
-impl<'a> std::str::FromStr for MIDINoteType {
+impl std::fmt::Display for MIDINoteType {
+    /// Format note type with sharps (i.e. `C#`), or with flats in the alternate form (`{:#}`)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.alternate() {
+            true => f.write_str(FlatSpelling.spell(*self)),
+            false => f.write_str(SharpSpelling.spell(*self)),
+        }
+    }
+}
+
+/// Note types in order of pitch class (0 is `C`, 11 is `B`)
+const PITCH_CLASSES: [MIDINoteType; 12] = [
+    MIDINoteType::C,
+    MIDINoteType::CSharp,
+    MIDINoteType::D,
+    MIDINoteType::DSharp,
+    MIDINoteType::E,
+    MIDINoteType::F,
+    MIDINoteType::FSharp,
+    MIDINoteType::G,
+    MIDINoteType::GSharp,
+    MIDINoteType::A,
+    MIDINoteType::ASharp,
+    MIDINoteType::B,
+];
+
+impl std::str::FromStr for MIDINoteType {
     type Err = ParseMIDINoteTypeError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -84,9 +112,15 @@ impl<'a> std::str::FromStr for MIDINoteType {
             | "cb"
             | "b" => Ok(Self::B),
             "rest" | "empty" => Ok(Self::Rest),
-            _ => Err(ParseMIDINoteTypeError::UnknownNoteType {
-                input: s.to_string(),
-            }),
+            // Any other spelling (i.e. "C♯" or "D𝄫")
+            _ => match parse_spelling(s) {
+                Some((letter, accidental)) => {
+                    Ok(PITCH_CLASSES[(letter.semitones() + accidental.offset()).rem_euclid(12) as usize])
+                },
+                None => Err(ParseMIDINoteTypeError::UnknownNoteType {
+                    input: s.to_string(),
+                }),
+            },
         }
     }
 }
@@ -96,12 +130,24 @@ impl<'a> std::str::FromStr for MIDINoteType {
 /// Credit to [@ldesgoui](https://github.com/ldesgoui) for the suggestion.
 #[derive(Debug, PartialEq, thiserror::Error)]
 pub enum ParseMIDINoteError {
-    #[error("Invalid note format (expected '<note>:<octave>', found {input})")]
+    #[error("Invalid note format (expected '<note>:<octave>' or '<note><octave>', found {input})")]
     InvalidNoteFormat { input: String },
     #[error(transparent)]
     InvalidOctave(#[from] std::num::ParseIntError),
     #[error(transparent)]
     UnknownNoteType(#[from] ParseMIDINoteTypeError),
+    #[error(transparent)]
+    OutOfRange(#[from] MIDINoteRangeError),
+}
+
+/// Error type for creating a [MIDINote](struct.MIDINote.html) outside of the
+/// MIDI note range (C:-1 to G:9)
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum MIDINoteRangeError {
+    #[error("Note {note_type:?}:{octave} is outside of the MIDI note range (0-127)")]
+    OutOfRange { note_type: MIDINoteType, octave: i32 },
+    #[error("Note number {number} is outside of the MIDI note range (0-127)")]
+    InvalidNoteNumber { number: u32 },
 }
 
 /// MIDI note
@@ -111,10 +157,12 @@ pub enum ParseMIDINoteError {
 /// `MIDINote { note_type: MIDINoteType::C, octave: 4 }`.  For a detailed table
 /// of MIDI notes and octave numbers, see document here:
 /// <https://www.cs.cmu.edu/~music/cmsip/readings/Standard-MIDI-file-format-updated.pdf>.
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+///
+/// Notes are ordered by pitch (i.e. `D:4 < C:5`), with rests ordered before all other notes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct MIDINote {
     pub note_type: MIDINoteType,
-    pub octave: u32,
+    pub octave: i32,
 }
 
 impl MIDINote {
@@ -130,65 +178,730 @@ impl MIDINote {
     /// ```rust
     /// // Middle C
     /// let note = libatm::MIDINote::new(libatm::MIDINoteType::C, 4);
-    /// assert_eq!(60, note.convert());
+    /// assert_eq!(Some(60), note.convert());
     /// ```
     ///
     /// # Notes
     ///
-    /// The `octave` parameter is not validated, but must be between
-    /// -1 and 9 in order to represent a valid MIDI note.
-    pub fn new(note_type: MIDINoteType, octave: u32) -> Self {
+    /// Panics if the note is outside of the MIDI note range (C:-1 to G:9).
+    /// See [try_new](#method.try_new) for a fallible alternative.
+    pub fn new(note_type: MIDINoteType, octave: i32) -> Self {
+        // C:-1 (0) <= note <= G:9 (127)
+        assert!(Self::is_valid(note_type, octave));
         Self { note_type, octave, }
     }
 
+    /// Create new `MIDINote`, or return an error if the note is outside
+    /// of the MIDI note range (C:-1 to G:9)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let note = libatm::MIDINote::try_new(libatm::MIDINoteType::C, -1).unwrap();
+    /// assert_eq!(Some(0), note.convert());
+    /// assert!(libatm::MIDINote::try_new(libatm::MIDINoteType::GSharp, 9).is_err());
+    /// ```
+    pub fn try_new(note_type: MIDINoteType, octave: i32) -> Result<Self, MIDINoteRangeError> {
+        match Self::is_valid(note_type, octave) {
+            true => Ok(Self { note_type, octave, }),
+            false => Err(MIDINoteRangeError::OutOfRange { note_type, octave }),
+        }
+    }
+
+    /// Name note using a [spelling policy](trait.MIDINoteSpelling.html)
+    ///
+    /// The name has the same format as accepted by [from_str](#method.from_str) (`<note>:<octave>`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::convert::TryFrom;
+    ///
+    /// let note = libatm::MIDINote::try_from(61u8).unwrap();
+    /// assert_eq!("C#:4", note.spell(&libatm::SharpSpelling));
+    /// assert_eq!("Db:4", note.spell(&libatm::FlatSpelling));
+    /// // Key of F major (one flat)
+    /// assert_eq!("Db:4", note.spell(&libatm::KeySpelling::new(-1)));
+    /// assert_eq!(Ok(note), note.spell(&libatm::FlatSpelling).parse::<libatm::MIDINote>());
+    /// ```
+    pub fn spell<S: MIDINoteSpelling + ?Sized>(&self, spelling: &S) -> String {
+        let octave = SpelledPitch::from_midi_note(self, spelling).map_or(self.octave, |pitch| pitch.octave);
+        format!("{}:{}", spelling.spell(self.note_type), octave)
+    }
+
+    /// Pitch of note (MIDI note number, without range check)
+    ///
+    /// Computed as `i64` so notes with any octave (i.e. created by setting the fields directly) can be compared.
+    fn pitch(&self) -> i64 {
+        (self.note_type as i64) + (self.octave as i64 + 1) * 12
+    }
+
+    /// Whether note type and octave are within the MIDI note range (rests are always valid)
+    fn is_valid(note_type: MIDINoteType, octave: i32) -> bool {
+        match note_type {
+            MIDINoteType::Rest => true,
+            _ => (-1..=9).contains(&octave) && (note_type as i32) + (octave + 1) * 12 <= 127,
+        }
+    }
+
     /// Convert MIDI note to an integer representation (MIDI note number)
     ///
-    /// The empty note (A.K.A. silence) is represented as `u32::max_value()`.
-    /// The MIDI Tuning Standard only represents pitches up to 127, but instead
-    /// of choosing some arbitrary number larger than 127, `u32::max_value()` is easily
-    /// distinguishable.
-    pub fn convert(&self) -> u32 {
+    /// Returns `None` for the empty note (A.K.A. silence), which does not have
+    /// a MIDI note number, and for notes outside of the MIDI note range (which can only
+    /// be created by setting the fields directly).
+    pub fn convert(&self) -> Option<u8> {
         match &self.note_type {
-            MIDINoteType::Rest => u32::max_value(),
-            _ => (self.note_type as u32) + (self.octave + 1) * 12,
+            MIDINoteType::Rest => None,
+            _ => match Self::is_valid(self.note_type, self.octave) {
+                true => Some(self.pitch() as u8),
+                false => None,
+            },
         }
     }
 }
 
-impl<'a> std::str::FromStr for MIDINote {
-    type Err = ParseMIDINoteError;
+impl Ord for MIDINote {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.note_type, other.note_type) {
+            // Rests are ordered by octave
+            (MIDINoteType::Rest, MIDINoteType::Rest) => self.octave.cmp(&other.octave),
+            (MIDINoteType::Rest, _) => std::cmp::Ordering::Less,
+            (_, MIDINoteType::Rest) => std::cmp::Ordering::Greater,
+            _ => self.pitch().cmp(&other.pitch()),
+        }
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Split input on ':' to get note type and octave
+impl PartialOrd for MIDINote {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Split note into note name and octave
+///
+/// Notes are either written as `<note>:<octave>`, or in scientific pitch notation as
+/// `<note><octave>` where the note name only contains letters and accidentals
+/// and the octave starts with a digit or `-`.
+fn split_note(s: &str) -> Result<(&str, &str), ParseMIDINoteError> {
+    let invalid_format = || ParseMIDINoteError::InvalidNoteFormat {
+        input: s.to_string(),
+    };
+    // Split input on ':' to get note name and octave
+    if s.contains(':') {
         let split_pair: Vec<&str> = s.split(':').collect();
         // Ensure is a pair (length is 2)
         if split_pair.len() != 2 {
-            return Err(ParseMIDINoteError::InvalidNoteFormat {
-                input: s.to_string(),
-            });
+            return Err(invalid_format());
+        }
+        return Ok((split_pair[0], split_pair[1]));
+    }
+    // Otherwise split input where the octave starts
+    let octave_start = s
+        .find(|c: char| !(c.is_alphabetic() || "#♯♭♮𝄪𝄫".contains(c)))
+        .ok_or_else(invalid_format)?;
+    let (name, octave) = s.split_at(octave_start);
+    match octave.starts_with(|c: char| c.is_ascii_digit() || c == '-') && !name.is_empty() {
+        true => Ok((name, octave)),
+        false => Err(invalid_format()),
+    }
+}
+
+impl std::str::FromStr for MIDINote {
+    type Err = ParseMIDINoteError;
+
+    /// Parse note as `<note>:<octave>` (i.e. `C#:4`) or in scientific
+    /// pitch notation as `<note><octave>` (i.e. `C#4` or `Bb-1`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, octave) = split_note(s)?;
+        // Parse MIDINoteType from note name
+        let note_type = MIDINoteType::from_str(name)?;
+        // Parse octave (as i32), and carry the octave
+        // if the note type crosses the B/C boundary
+        let octave = octave.parse::<i32>()?;
+        let octave = octave
+            .checked_add(octave_carry(name))
+            .ok_or(MIDINoteRangeError::OutOfRange { note_type, octave })?;
+        Ok(Self::try_new(note_type, octave)?)
+    }
+}
+
+impl std::fmt::Display for MIDINote {
+    /// Format note as `<note>:<octave>` with sharps (i.e. `C#:4`), or with flats
+    /// in the alternate form (`{:#}`).  The result can always be parsed back
+    /// into the same `MIDINote`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_note(self, f, false)
+    }
+}
+
+/// Format note in colon or scientific pitch notation (with flats in the alternate form)
+fn fmt_note(note: &MIDINote, f: &mut std::fmt::Formatter<'_>, scientific: bool) -> std::fmt::Result {
+    let separator = match scientific {
+        true => "",
+        false => ":",
+    };
+    match f.alternate() {
+        true => write!(f, "{:#}{}{}", note.note_type, separator, note.octave),
+        false => write!(f, "{}{}{}", note.note_type, separator, note.octave),
+    }
+}
+
+/// Format notes separated by `,` (see: `fmt_note`)
+fn fmt_notes<'a, I>(notes: I, f: &mut std::fmt::Formatter<'_>, scientific: bool) -> std::fmt::Result
+where
+    I: Iterator<Item=&'a MIDINote>,
+{
+    for (idx, note) in notes.enumerate() {
+        if idx > 0 {
+            f.write_str(",")?;
+        }
+        fmt_note(note, f, scientific)?;
+    }
+    Ok(())
+}
+
+impl std::convert::TryFrom<u8> for MIDINote {
+    type Error = MIDINoteRangeError;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        Self::try_from(number as u32)
+    }
+}
+
+impl std::convert::TryFrom<u32> for MIDINote {
+    type Error = MIDINoteRangeError;
+
+    /// Create `MIDINote` from MIDI note number (between 0 and 127)
+    ///
+    /// Note types are always sharps (i.e. 61 is `CSharp:4`), use a
+    /// [spelling policy](trait.MIDINoteSpelling.html) to name notes differently.
+    fn try_from(number: u32) -> Result<Self, Self::Error> {
+        if number > 127 {
+            return Err(MIDINoteRangeError::InvalidNoteNumber { number });
+        }
+        let note_type = PITCH_CLASSES[(number % 12) as usize];
+        Ok(Self { note_type, octave: (number / 12) as i32 - 1 })
+    }
+}
+
+/// Enharmonic spelling policy
+///
+/// Determines how the note type of a [MIDINote](struct.MIDINote.html) is named
+/// (see: [MIDINote::spell](struct.MIDINote.html#method.spell)), for example
+/// whether MIDI note number 61 is written as `C#:4` or `Db:4`.
+pub trait MIDINoteSpelling {
+    /// Name of note type (accepted by [MIDINoteType::from_str](enum.MIDINoteType.html))
+    fn spell(&self, note_type: MIDINoteType) -> &'static str;
+}
+
+/// Spell black keys as sharps (i.e. `C#`)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SharpSpelling;
+
+impl MIDINoteSpelling for SharpSpelling {
+    fn spell(&self, note_type: MIDINoteType) -> &'static str {
+        match note_type {
+            MIDINoteType::C => "C",
+            MIDINoteType::CSharp => "C#",
+            MIDINoteType::D => "D",
+            MIDINoteType::DSharp => "D#",
+            MIDINoteType::E => "E",
+            MIDINoteType::F => "F",
+            MIDINoteType::FSharp => "F#",
+            MIDINoteType::G => "G",
+            MIDINoteType::GSharp => "G#",
+            MIDINoteType::A => "A",
+            MIDINoteType::ASharp => "A#",
+            MIDINoteType::B => "B",
+            MIDINoteType::Rest => "Rest",
+        }
+    }
+}
+
+/// Spell black keys as flats (i.e. `Db`)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FlatSpelling;
+
+impl MIDINoteSpelling for FlatSpelling {
+    fn spell(&self, note_type: MIDINoteType) -> &'static str {
+        match note_type {
+            MIDINoteType::CSharp => "Db",
+            MIDINoteType::DSharp => "Eb",
+            MIDINoteType::FSharp => "Gb",
+            MIDINoteType::GSharp => "Ab",
+            MIDINoteType::ASharp => "Bb",
+            _ => SharpSpelling.spell(note_type),
+        }
+    }
+}
+
+/// Spell notes according to a key signature
+///
+/// Notes in the scale of the key are spelled as written in the key signature (i.e. `E#`
+/// and `B#` in C# major, `Cb` in Gb major).  Other notes use sharps in keys with sharps (and
+/// C major/A minor), and flats in keys with flats.  The number of sharps (positive) or flats
+/// (negative) has the same meaning as in the [KeySignature](../midi_event/enum.MIDIMetaEvent.html#variant.KeySignature)
+/// meta event, and is clamped between -7 and 7.
+///
+/// # Examples
+///
+/// ```rust
+/// // Key of C# major (seven sharps)
+/// let key = libatm::KeySpelling::new(7);
+/// assert_eq!("E#:4", libatm::MIDINote::new(libatm::MIDINoteType::F, 4).spell(&key));
+/// assert_eq!("B#:4", libatm::MIDINote::new(libatm::MIDINoteType::C, 5).spell(&key));
+/// // Key of Gb major (six flats)
+/// let key = libatm::KeySpelling::new(-6);
+/// assert_eq!("Cb:4", libatm::MIDINote::new(libatm::MIDINoteType::B, 3).spell(&key));
+/// assert_eq!(Ok(libatm::MIDINote::new(libatm::MIDINoteType::B, 3)), "Cb:4".parse::<libatm::MIDINote>());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct KeySpelling {
+    pub sharps_flats: i8,
+}
+
+impl KeySpelling {
+    /// Create new `KeySpelling`
+    pub fn new(sharps_flats: i8) -> Self {
+        Self { sharps_flats }
+    }
+}
+
+impl MIDINoteSpelling for KeySpelling {
+    fn spell(&self, note_type: MIDINoteType) -> &'static str {
+        const SHARPS: [NoteLetter; 7] = [
+            NoteLetter::F,
+            NoteLetter::C,
+            NoteLetter::G,
+            NoteLetter::D,
+            NoteLetter::A,
+            NoteLetter::E,
+            NoteLetter::B,
+        ];
+        let sharps_flats = self.sharps_flats.clamp(-7, 7);
+        let scale = SHARPS.iter().enumerate().map(|(idx, letter)| {
+            // Sharps are added in order (F, C, G, ...), flats in reverse order (B, E, A, ...)
+            let accidental = match sharps_flats {
+                n if (idx as i8) < n => Accidental::Sharp,
+                n if (6 - idx as i8) < -n => Accidental::Flat,
+                _ => Accidental::Natural,
+            };
+            (*letter, accidental)
+        });
+        for (letter, accidental) in scale {
+            if PITCH_CLASSES[(letter.semitones() + accidental.offset()).rem_euclid(12) as usize] == note_type {
+                return NOTE_NAMES[letter as usize][accidental as usize];
+            }
+        }
+        match sharps_flats < 0 {
+            true => FlatSpelling.spell(note_type),
+            false => SharpSpelling.spell(note_type),
         }
-        // Parse MIDINoteType from first item in pair
-        let note_type = MIDINoteType::from_str(split_pair[0])?;
-        // Parse octave (as u32) from second item in pair
-        // TODO: Enforce octave range of -1 to 9
-        let octave = split_pair[1].parse::<u32>()?;
-        Ok(Self { note_type, octave })
     }
 }
 
-/// Error type for parsing [MIDINoteSet](struct.MIDINoteSet.html) and 
-/// Vec<[MIDINote](struct.MIDINote.html)> from `&str`
+/// Names of each [NoteLetter](enum.NoteLetter.html) with each [Accidental](enum.Accidental.html)
+const NOTE_NAMES: [[&str; 5]; 7] = [
+    ["Cbb", "Cb", "C", "C#", "Cx"],
+    ["Dbb", "Db", "D", "D#", "Dx"],
+    ["Ebb", "Eb", "E", "E#", "Ex"],
+    ["Fbb", "Fb", "F", "F#", "Fx"],
+    ["Gbb", "Gb", "G", "G#", "Gx"],
+    ["Abb", "Ab", "A", "A#", "Ax"],
+    ["Bbb", "Bb", "B", "B#", "Bx"],
+];
+
+/// Octave carry of a note type name
+///
+/// `B#` is the same key as `C` in the next octave (i.e. `B#:4` is `C:5`), and `Cb` is
+/// the same key as `B` in the previous octave (i.e. `Cb:4` is `B:3`).  Likewise for
+/// double sharps and flats (i.e. `Bx:4` is `C#:5`).  All other note type names
+/// (including `E#` and `Fb`) are in the same octave as written.
+fn octave_carry(note_type: &str) -> i32 {
+    match parse_spelling(note_type) {
+        Some((letter, accidental)) => (letter.semitones() + accidental.offset()).div_euclid(12),
+        None => 0,
+    }
+}
+
+/// Letter name of a note (without accidental)
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum NoteLetter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+impl NoteLetter {
+    /// Number of semitones above `C`
+    pub fn semitones(&self) -> i32 {
+        match self {
+            Self::C => 0,
+            Self::D => 2,
+            Self::E => 4,
+            Self::F => 5,
+            Self::G => 7,
+            Self::A => 9,
+            Self::B => 11,
+        }
+    }
+}
+
+/// Accidental applied to a [NoteLetter](enum.NoteLetter.html)
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Accidental {
+    DoubleFlat,
+    Flat,
+    Natural,
+    Sharp,
+    DoubleSharp,
+}
+
+impl Accidental {
+    /// Number of semitones the accidental raises (positive) or lowers (negative) a note by
+    pub fn offset(&self) -> i32 {
+        match self {
+            Self::DoubleFlat => -2,
+            Self::Flat => -1,
+            Self::Natural => 0,
+            Self::Sharp => 1,
+            Self::DoubleSharp => 2,
+        }
+    }
+
+    /// Symbol of accidental, either ASCII (i.e. `#`) or Unicode (i.e. `♯`)
+    fn symbol(&self, unicode: bool) -> &'static str {
+        match (self, unicode) {
+            (Self::DoubleFlat, false) => "bb",
+            (Self::DoubleFlat, true) => "𝄫",
+            (Self::Flat, false) => "b",
+            (Self::Flat, true) => "♭",
+            (Self::Natural, _) => "",
+            (Self::Sharp, false) => "#",
+            (Self::Sharp, true) => "♯",
+            (Self::DoubleSharp, false) => "x",
+            (Self::DoubleSharp, true) => "𝄪",
+        }
+    }
+}
+
+/// Parse note name (letter followed by optional accidental, case insensitive)
+fn parse_spelling(name: &str) -> Option<(NoteLetter, Accidental)> {
+    let mut chars = name.chars();
+    let letter = match chars.next()?.to_ascii_lowercase() {
+        'c' => NoteLetter::C,
+        'd' => NoteLetter::D,
+        'e' => NoteLetter::E,
+        'f' => NoteLetter::F,
+        'g' => NoteLetter::G,
+        'a' => NoteLetter::A,
+        'b' => NoteLetter::B,
+        _ => return None,
+    };
+    let accidental = match chars.as_str().to_lowercase().as_str() {
+        "bb" | "♭♭" | "𝄫" | "doubleflat" => Accidental::DoubleFlat,
+        "b" | "♭" | "flat" => Accidental::Flat,
+        "" | "♮" | "natural" => Accidental::Natural,
+        "#" | "♯" | "sharp" => Accidental::Sharp,
+        "x" | "##" | "♯♯" | "𝄪" | "doublesharp" => Accidental::DoubleSharp,
+        _ => return None,
+    };
+    Some((letter, accidental))
+}
+
+/// Spelled pitch
+///
+/// Represents a pitch as written in notation: a [letter](enum.NoteLetter.html), an
+/// [accidental](enum.Accidental.html) and the written octave.  Unlike [MIDINote](struct.MIDINote.html),
+/// which only represents the key on the piano, `Db:4` and `C#:4` are different spelled pitches.
+/// Spelled pitches convert to `MIDINote` without loss of pitch, and a `MIDINote` can be spelled
+/// with any [spelling policy](trait.MIDINoteSpelling.html).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::convert::TryFrom;
+///
+/// let pitch = "Cb:4".parse::<libatm::SpelledPitch>().unwrap();
+/// assert_eq!(libatm::NoteLetter::C, pitch.letter);
+/// assert_eq!(libatm::Accidental::Flat, pitch.accidental);
+/// let note = libatm::MIDINote::try_from(pitch).unwrap();
+/// assert_eq!(libatm::MIDINote::new(libatm::MIDINoteType::B, 3), note);
+/// assert_eq!("C𝄪:4", format!("{:#}", "Cx:4".parse::<libatm::SpelledPitch>().unwrap()));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SpelledPitch {
+    pub letter: NoteLetter,
+    pub accidental: Accidental,
+    /// Written octave (i.e. `B#:4` is written in octave 4, but is the same key as `C:5`)
+    pub octave: i32,
+}
+
+impl SpelledPitch {
+    /// Create new `SpelledPitch`
+    pub fn new(letter: NoteLetter, accidental: Accidental, octave: i32) -> Self {
+        Self { letter, accidental, octave }
+    }
+
+    /// Spell `MIDINote` with a [spelling policy](trait.MIDINoteSpelling.html)
+    ///
+    /// Returns `None` for rests, if the spelling policy produces an unknown note name, and
+    /// if the written octave does not fit in an `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let note = libatm::MIDINote::new(libatm::MIDINoteType::DSharp, 4);
+    /// let pitch = libatm::SpelledPitch::from_midi_note(&note, &libatm::FlatSpelling).unwrap();
+    /// assert_eq!("Eb:4", pitch.to_string());
+    /// ```
+    pub fn from_midi_note<S: MIDINoteSpelling + ?Sized>(note: &MIDINote, spelling: &S) -> Option<Self> {
+        let (letter, accidental) = parse_spelling(spelling.spell(note.note_type))?;
+        // Written octave may differ from the octave of the note (i.e. B# and Cb)
+        let offset = (letter.semitones() + accidental.offset()) as i64;
+        let octave = <i32 as std::convert::TryFrom<i64>>::try_from((note.pitch() - offset).div_euclid(12) - 1).ok()?;
+        Some(Self { letter, accidental, octave })
+    }
+
+    /// Note type (pitch class) of this spelled pitch
+    pub fn note_type(&self) -> MIDINoteType {
+        PITCH_CLASSES[(self.letter.semitones() + self.accidental.offset()).rem_euclid(12) as usize]
+    }
+}
+
+impl std::convert::TryFrom<SpelledPitch> for MIDINote {
+    type Error = MIDINoteRangeError;
+
+    fn try_from(pitch: SpelledPitch) -> Result<Self, Self::Error> {
+        let note_type = pitch.note_type();
+        let octave = pitch
+            .octave
+            .checked_add((pitch.letter.semitones() + pitch.accidental.offset()).div_euclid(12))
+            .ok_or(MIDINoteRangeError::OutOfRange { note_type, octave: pitch.octave })?;
+        Self::try_new(note_type, octave)
+    }
+}
+
+impl std::str::FromStr for SpelledPitch {
+    type Err = ParseMIDINoteError;
+
+    /// Parse spelled pitch with the same formats as [MIDINote](struct.MIDINote.html)
+    /// (`<note>:<octave>` or `<note><octave>`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, octave) = split_note(s)?;
+        let (letter, accidental) = parse_spelling(name).ok_or_else(|| {
+            ParseMIDINoteTypeError::UnknownNoteType {
+                input: name.to_string(),
+            }
+        })?;
+        let octave = octave.parse::<i32>()?;
+        Ok(Self { letter, accidental, octave })
+    }
+}
+
+impl std::fmt::Display for SpelledPitch {
+    /// Format spelled pitch as `<letter><accidental>:<octave>`, with Unicode
+    /// accidentals in the alternate form (`{:#}`)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}{}:{}", self.letter, self.accidental.symbol(f.alternate()), self.octave)
+    }
+}
+
+/// Error type for parsing [MIDINoteSet](struct.MIDINoteSet.html), 
+/// Vec<[MIDINote](struct.MIDINote.html)>, and Vec<[MIDITimedNote](struct.MIDITimedNote.html)> from `&str`
 #[derive(Debug, PartialEq, thiserror::Error)]
 pub enum ParseMIDINoteSequenceError {
     #[error("Invalid note at index {0}")]
     ParseMIDINote(usize, #[source] ParseMIDINoteError),
+    #[error("Invalid timed note at index {0}")]
+    ParseMIDITimedNote(usize, #[source] ParseMIDITimedNoteError),
+    #[error("Invalid range at index {0} (expected '<note>..<note>', from low to high)")]
+    InvalidRange(usize),
+    #[error("Invalid scale at index {0}")]
+    ParseMIDIScale(usize, #[source] ParseMIDIScaleError),
+}
+
+/// Error type for parsing [MIDIScale](enum.MIDIScale.html) and scale expressions from `&str`
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ParseMIDIScaleError {
+    #[error("Unknown scale {input}")]
+    UnknownScale { input: String },
+    #[error("Invalid scale format (expected '<note>:<scale>:<octave>' or 'scale(<note> <scale>, <octave>)', found {input})")]
+    InvalidScaleFormat { input: String },
+    #[error(transparent)]
+    InvalidRoot(#[from] ParseMIDINoteError),
+    #[error(transparent)]
+    OutOfRange(#[from] MIDINoteRangeError),
+}
+
+/// Scale (or mode)
+///
+/// Determines the notes of a scale expression when parsing a [MIDINoteSet](struct.MIDINoteSet.html).
+/// Scale names are case insensitive, and spaces, `-` and `_` are ignored (i.e. `harmonic minor`,
+/// `harmonic-minor` and `HarmonicMinor` are the same scale).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MIDIScale {
+    /// Major scale (A.K.A. ionian mode)
+    Major,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    /// Natural minor scale (A.K.A. aeolian mode)
+    Minor,
+    Locrian,
+    HarmonicMinor,
+    MelodicMinor,
+    MajorPentatonic,
+    MinorPentatonic,
+    Blues,
+    WholeTone,
+    Chromatic,
+}
+
+impl MIDIScale {
+    /// Intervals of the scale in semitones above the root (within one octave)
+    pub fn intervals(&self) -> &'static [u8] {
+        match self {
+            Self::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Self::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Self::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Self::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Self::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Self::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Self::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            Self::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            Self::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Self::MajorPentatonic => &[0, 2, 4, 7, 9],
+            Self::MinorPentatonic => &[0, 3, 5, 7, 10],
+            Self::Blues => &[0, 3, 5, 6, 7, 10],
+            Self::WholeTone => &[0, 2, 4, 6, 8, 10],
+            Self::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    /// Generate one octave of the scale, starting at `root`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let root = "D:4".parse::<libatm::MIDINote>().unwrap();
+    /// let notes = libatm::MIDIScale::Dorian.gen_notes(&root).unwrap();
+    /// assert_eq!("D:4,E:4,F:4,G:4,A:4,B:4,C:5", libatm::MIDINoteVec(notes).to_string());
+    /// ```
+    pub fn gen_notes(&self, root: &MIDINote) -> Result<Vec<MIDINote>, MIDINoteRangeError> {
+        let root_number = match root.convert() {
+            Some(number) => number as u32,
+            None => return Err(MIDINoteRangeError::OutOfRange { note_type: root.note_type, octave: root.octave }),
+        };
+        self
+            .intervals()
+            .iter()
+            .map(|interval| <MIDINote as std::convert::TryFrom<u32>>::try_from(root_number + *interval as u32))
+            .collect::<Result<Vec<MIDINote>, MIDINoteRangeError>>()
+    }
+}
+
+impl std::str::FromStr for MIDIScale {
+    type Err = ParseMIDIScaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s
+            .chars()
+            .filter(|c| !(c.is_whitespace() || *c == '-' || *c == '_'))
+            .collect::<String>()
+            .to_lowercase();
+        match name.as_str() {
+            "major" | "ionian" => Ok(Self::Major),
+            "dorian" => Ok(Self::Dorian),
+            "phrygian" => Ok(Self::Phrygian),
+            "lydian" => Ok(Self::Lydian),
+            "mixolydian" => Ok(Self::Mixolydian),
+            "minor" | "naturalminor" | "aeolian" => Ok(Self::Minor),
+            "locrian" => Ok(Self::Locrian),
+            "harmonicminor" => Ok(Self::HarmonicMinor),
+            "melodicminor" => Ok(Self::MelodicMinor),
+            "pentatonic" | "majorpentatonic" => Ok(Self::MajorPentatonic),
+            "minorpentatonic" => Ok(Self::MinorPentatonic),
+            "blues" => Ok(Self::Blues),
+            "wholetone" => Ok(Self::WholeTone),
+            "chromatic" => Ok(Self::Chromatic),
+            _ => Err(ParseMIDIScaleError::UnknownScale {
+                input: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Parse scale expression (`<note>:<scale>:<octave>` or `scale(<note> <scale>, <octave>)`)
+fn parse_scale(s: &str) -> Result<Vec<MIDINote>, ParseMIDIScaleError> {
+    let invalid_format = || ParseMIDIScaleError::InvalidScaleFormat {
+        input: s.to_string(),
+    };
+    let (root, scale, octave) = match s.strip_prefix("scale(").and_then(|inner| inner.strip_suffix(')')) {
+        Some(inner) => {
+            let (root_scale, octave) = inner.split_once(',').ok_or_else(invalid_format)?;
+            let (root, scale) = root_scale.trim().split_once(' ').ok_or_else(invalid_format)?;
+            (root, scale, octave.trim())
+        },
+        None => {
+            let split_triple: Vec<&str> = s.split(':').collect();
+            // Ensure is a triple (length is 3)
+            if split_triple.len() != 3 {
+                return Err(invalid_format());
+            }
+            (split_triple[0], split_triple[1], split_triple[2])
+        },
+    };
+    let root = format!("{}:{}", root, octave).parse::<MIDINote>()?;
+    let scale = scale.parse::<MIDIScale>()?;
+    Ok(scale.gen_notes(&root)?)
+}
+
+/// Parse range expression (`<note>..<note>`, inclusive)
+fn parse_range(s: &str) -> Option<Result<Vec<MIDINote>, ParseMIDINoteError>> {
+    let (low, high) = s.split_once("..")?;
+    let range = low.parse::<MIDINote>().and_then(|low| Ok((low, high.parse::<MIDINote>()?)));
+    Some(range.map(|(low, high)| match (low.convert(), high.convert()) {
+        (Some(low), Some(high)) => (low..=high)
+            .map(|number| <MIDINote as std::convert::TryFrom<u8>>::try_from(number).unwrap())
+            .collect::<Vec<MIDINote>>(),
+        _ => Vec::new(),
+    }))
+}
+
+/// Split input on `,`, except within parentheses
+fn split_items(s: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&s[start..idx]);
+                start = idx + 1;
+            },
+            _ => (),
+        }
+    }
+    items.push(&s[start..]);
+    items
 }
 
 /// Container for set of `MIDINote`
 ///
 /// Implements the [FromStr](https://doc.rust-lang.org/nightly/core/str/trait.FromStr.html)
 /// trait as a convenience method for parsing a set of `MIDINote` (from a command line
-/// argument).
+/// argument).  Notes are iterated in order of pitch, from low to high (with rests first).
+/// Besides single notes, the input may contain ranges of notes (`C:4..C:5`, inclusive) and
+/// one octave of a [scale](enum.MIDIScale.html) (`D:dorian:4` or `scale(C major, 4)`).
 ///
 /// # Examples
 ///
@@ -203,6 +916,9 @@ pub enum ParseMIDINoteSequenceError {
 ///     libatm::MIDINote::new(libatm::MIDINoteType::DSharp, 3),
 /// ].into_iter().collect::<std::collections::BTreeSet<libatm::MIDINote>>());
 /// assert_eq!(expected, note_set);
+/// // Parse C major scale and the chromatic span above it
+/// let note_set = "scale(C major, 4),C4..E4".parse::<libatm::MIDINoteSet>().unwrap();
+/// assert_eq!("C:4,C#:4,D:4,D#:4,E:4,F:4,G:4,A:4,B:4", note_set.to_string());
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct MIDINoteSet(pub std::collections::BTreeSet<MIDINote>);
@@ -221,18 +937,41 @@ impl std::str::FromStr for MIDINoteSet {
 
     /// Credit to [@ldesgoui](https://github.com/ldesgoui) for the implementation
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let notes = s
-            .split(',')
-            .enumerate()
-            .map(|(idx, pair)| {
-                pair.parse::<MIDINote>()
-                    .map_err(|err| ParseMIDINoteSequenceError::ParseMIDINote(idx, err))
-            })
-            .collect::<Result<std::collections::BTreeSet<MIDINote>, ParseMIDINoteSequenceError>>()?;
+        // Empty input is an empty set
+        if s.is_empty() {
+            return Ok(Self(std::collections::BTreeSet::new()));
+        }
+        let mut notes = std::collections::BTreeSet::new();
+        for (idx, item) in split_items(s).into_iter().enumerate() {
+            // Range (i.e. "C:4..C:5")
+            if let Some(range) = parse_range(item) {
+                let range = range.map_err(|err| ParseMIDINoteSequenceError::ParseMIDINote(idx, err))?;
+                if range.is_empty() {
+                    return Err(ParseMIDINoteSequenceError::InvalidRange(idx));
+                }
+                notes.extend(range);
+            // Scale (i.e. "D:dorian:4" or "scale(C major, 4)")
+            } else if item.starts_with("scale(") || item.matches(':').count() == 2 {
+                let scale = parse_scale(item).map_err(|err| ParseMIDINoteSequenceError::ParseMIDIScale(idx, err))?;
+                notes.extend(scale);
+            } else {
+                let note = item
+                    .parse::<MIDINote>()
+                    .map_err(|err| ParseMIDINoteSequenceError::ParseMIDINote(idx, err))?;
+                notes.insert(note);
+            }
+        }
         Ok(Self(notes))
     }
 }
 
+impl std::fmt::Display for MIDINoteSet {
+    /// Format notes as `<note>:<octave>` separated by `,` (see: [MIDINote](struct.MIDINote.html#impl-Display))
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_notes(self.0.iter(), f, false)
+    }
+}
+
 /// Container for vector of `MIDINote`
 ///
 /// Implements the [FromStr](https://doc.rust-lang.org/nightly/core/str/trait.FromStr.html)
@@ -269,6 +1008,10 @@ impl std::str::FromStr for MIDINoteVec {
     type Err = ParseMIDINoteSequenceError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Empty input is an empty sequence
+        if s.is_empty() {
+            return Ok(Self(Vec::new()));
+        }
         let notes = s
             .split(',')
             .enumerate()
@@ -281,6 +1024,13 @@ impl std::str::FromStr for MIDINoteVec {
     }
 }
 
+impl std::fmt::Display for MIDINoteVec {
+    /// Format notes as `<note>:<octave>` separated by `,` (see: [MIDINote](struct.MIDINote.html#impl-Display))
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_notes(self.0.iter(), f, false)
+    }
+}
+
 impl std::iter::FromIterator<MIDINote> for MIDINoteVec {
     // Create MIDINoteVec from iterator over MIDINote
     fn from_iter<I: IntoIterator<Item=MIDINote>>(iter: I) -> Self {
@@ -289,16 +1039,249 @@ impl std::iter::FromIterator<MIDINote> for MIDINoteVec {
 }
 
 impl From<MIDINoteSet> for MIDINoteVec {
-    // Allow conversion from MIDINoteSet to Vec<MIDINote>
+    // Allow conversion from MIDINoteSet to Vec<MIDINote> (ordered by pitch)
     fn from(set: MIDINoteSet) -> Self {
         set.0.into_iter().collect::<MIDINoteVec>()
     }
 }
 
 impl From<&MIDINoteSet> for MIDINoteVec {
-    // Allow conversion from &MIDINoteSet to Vec<MIDINote>
+    // Allow conversion from &MIDINoteSet to Vec<MIDINote> (ordered by pitch)
     fn from(set: &MIDINoteSet) -> Self {
-        set.iter().map(|n| n.clone()).collect::<MIDINoteVec>()
+        set.iter().copied().collect::<MIDINoteVec>()
+    }
+}
+
+/// Format notes in scientific pitch notation
+///
+/// Wraps a [MIDINote](struct.MIDINote.html), [MIDINoteSet](struct.MIDINoteSet.html) or
+/// [MIDINoteVec](struct.MIDINoteVec.html) to format notes as `<note><octave>` (i.e. `C#4`)
+/// instead of `<note>:<octave>`.  Like the default format, the alternate form (`{:#}`) uses flats,
+/// and the result can always be parsed back into the same notes.
+///
+/// # Examples
+///
+/// ```rust
+/// let sequence = "C:-1,C#:4,G#:8".parse::<libatm::MIDINoteVec>().unwrap();
+/// assert_eq!("C:-1,C#:4,G#:8", sequence.to_string());
+/// assert_eq!("C:-1,Db:4,Ab:8", format!("{:#}", sequence));
+/// assert_eq!("C-1,C#4,G#8", libatm::ScientificPitch(&sequence).to_string());
+/// assert_eq!("C-1,Db4,Ab8", format!("{:#}", libatm::ScientificPitch(&sequence)));
+/// assert_eq!(Ok(sequence), "C-1,Db4,Ab8".parse::<libatm::MIDINoteVec>());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ScientificPitch<'a, T: ?Sized>(pub &'a T);
+
+impl std::fmt::Display for ScientificPitch<'_, MIDINote> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_note(self.0, f, true)
+    }
+}
+
+impl std::fmt::Display for ScientificPitch<'_, MIDINoteSet> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_notes(self.0.iter(), f, true)
+    }
+}
+
+impl std::fmt::Display for ScientificPitch<'_, MIDINoteVec> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_notes(self.0.iter(), f, true)
+    }
+}
+
+/// MIDI note with timing
+///
+/// Places a [MIDINote](struct.MIDINote.html) in a track, starting at an absolute tick
+/// (relative to the start of the track) and held for a number of ticks.  The meaning
+/// of a tick is determined by the `division` value present in the
+/// [MIDIHeader](../midi_file/struct.MIDIHeader.html).  Multiple timed notes may start at
+/// the same tick (chords) or overlap.  Notes without a velocity are played with the velocity
+/// of the [track](../midi_file/struct.MIDITrack.html) they are part of.
+///
+/// # Examples
+///
+/// ```rust
+/// // C major triad held for two ticks, followed by a C held for one tick
+/// let chord = vec![libatm::MIDINoteType::C, libatm::MIDINoteType::E, libatm::MIDINoteType::G]
+///     .into_iter()
+///     .map(|note_type| libatm::MIDITimedNote::new(libatm::MIDINote::new(note_type, 4), 0, 2))
+///     .chain(std::iter::once(libatm::MIDITimedNote::new(libatm::MIDINote::new(libatm::MIDINoteType::C, 5), 2, 1)))
+///     .collect::<Vec<libatm::MIDITimedNote>>();
+/// assert_eq!(Some(3), chord[3].end());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MIDITimedNote {
+    pub note: MIDINote,
+    /// Tick at which the note is pressed
+    pub start: u32,
+    /// Number of ticks the note is held for
+    pub duration: u32,
+    /// Velocity with which to play the note (clamped between 1 and 127 when written)
+    pub velocity: Option<u8>,
+}
+
+impl MIDITimedNote {
+    /// Create new `MIDITimedNote` (without velocity)
+    pub fn new(note: MIDINote, start: u32, duration: u32) -> Self {
+        Self { note, start, duration, velocity: None }
+    }
+
+    /// Tick at which the note is released
+    ///
+    /// Returns `None` if the note would be released after the last tick (`u32::MAX`).
+    pub fn end(&self) -> Option<u32> {
+        self.start.checked_add(self.duration)
+    }
+}
+
+/// Error type for parsing [MIDITimedNote](struct.MIDITimedNote.html) from `&str`
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ParseMIDITimedNoteError {
+    #[error("Invalid timed note format (expected '<note>[@<start>]/<duration>', found {input})")]
+    InvalidTimedNoteFormat { input: String },
+    #[error(transparent)]
+    InvalidTiming(#[from] std::num::ParseIntError),
+    #[error(transparent)]
+    InvalidNote(#[from] ParseMIDINoteError),
+    #[error("Timed note released after the last tick ({}), found {input}", u32::MAX)]
+    TickOverflow { input: String },
+}
+
+/// Parse note, (optional) start tick, and duration from `&str`
+fn parse_timed_note(s: &str) -> Result<(MIDINote, Option<u32>, u32), ParseMIDITimedNoteError> {
+    // Split input on '/' to get note (and start) and duration
+    let split_pair: Vec<&str> = s.split('/').collect();
+    if split_pair.len() != 2 {
+        return Err(ParseMIDITimedNoteError::InvalidTimedNoteFormat {
+            input: s.to_string(),
+        });
+    }
+    let duration = split_pair[1].parse::<u32>()?;
+    // Split note on '@' to get (optional) start
+    let (note, start) = match split_pair[0].split_once('@') {
+        Some((note, start)) => (note, Some(start.parse::<u32>()?)),
+        None => (split_pair[0], None),
+    };
+    Ok((note.parse::<MIDINote>()?, start, duration))
+}
+
+impl std::str::FromStr for MIDITimedNote {
+    type Err = ParseMIDITimedNoteError;
+
+    /// Parse timed note with format `<note>[@<start>]/<duration>` (start defaults to 0)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (note, start, duration) = parse_timed_note(s)?;
+        let timed_note = Self::new(note, start.unwrap_or(0), duration);
+        match timed_note.end() {
+            Some(_) => Ok(timed_note),
+            None => Err(ParseMIDITimedNoteError::TickOverflow { input: s.to_string() }),
+        }
+    }
+}
+
+/// Container for vector of `MIDITimedNote`
+///
+/// Can be used in place of [MIDINoteVec](struct.MIDINoteVec.html) to represent
+/// melodies with rhythm (see: [MIDIFile::from_timed_notes](../midi_file/struct.MIDIFile.html#method.from_timed_notes)).
+/// Implements the [FromStr](https://doc.rust-lang.org/nightly/core/str/trait.FromStr.html)
+/// trait as a convenience method for parsing a list of `MIDITimedNote`, where each note has the
+/// format `<note>[@<start>]/<duration>`.  Notes without an explicit start tick start
+/// when the previous note is released.
+///
+/// # Examples
+///
+/// ```rust
+/// // Parse half-note C, two quarter-note Ds, and a C an octave higher played over the last D
+/// // (with a division of 1)
+/// let notes = "C:4/2,D:4/1,D:4/1,C:5@3/2".parse::<libatm::MIDITimedNoteVec>().unwrap();
+/// let expected = libatm::MIDITimedNoteVec(vec![
+///     libatm::MIDITimedNote::new(libatm::MIDINote::new(libatm::MIDINoteType::C, 4), 0, 2),
+///     libatm::MIDITimedNote::new(libatm::MIDINote::new(libatm::MIDINoteType::D, 4), 2, 1),
+///     libatm::MIDITimedNote::new(libatm::MIDINote::new(libatm::MIDINoteType::D, 4), 3, 1),
+///     libatm::MIDITimedNote::new(libatm::MIDINote::new(libatm::MIDINoteType::C, 5), 3, 2),
+/// ]);
+/// assert_eq!(expected, notes);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MIDITimedNoteVec(pub Vec<MIDITimedNote>);
+
+impl MIDITimedNoteVec {
+    /// Create `MIDITimedNoteVec` from sequence of notes and their durations
+    ///
+    /// Each note starts when the previous note is released.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let sequence = "C:4,D:4,E:4".parse::<libatm::MIDINoteVec>().unwrap();
+    /// let notes = libatm::MIDITimedNoteVec::from_rhythm(&sequence, &[2, 1, 1]);
+    /// assert_eq!(3, notes[2].start);
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// Panics if there is not exactly one duration per note, or if the
+    /// last note would be released after the last tick (`u32::MAX`).
+    pub fn from_rhythm(sequence: &MIDINoteVec, durations: &[u32]) -> Self {
+        // One duration per note
+        assert_eq!(sequence.len(), durations.len());
+        let mut start = 0u32;
+        sequence
+            .iter()
+            .zip(durations.iter())
+            .map(|(note, duration)| {
+                let timed_note = MIDITimedNote::new(*note, start, *duration);
+                start = timed_note.end().expect("note released after the last tick");
+                timed_note
+            })
+            .collect::<MIDITimedNoteVec>()
+    }
+
+    /// Tick at which the last note is released
+    ///
+    /// Returns `None` if any note would be released after the last tick (`u32::MAX`).
+    pub fn end(&self) -> Option<u32> {
+        self.iter().try_fold(0, |end, note| Some(end.max(note.end()?)))
+    }
+}
+
+impl std::ops::Deref for MIDITimedNoteVec {
+    type Target = Vec<MIDITimedNote>;
+
+    /// Allow dereferencing of tuple struct to underlying vector
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for MIDITimedNoteVec {
+    type Err = ParseMIDINoteSequenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut next_start = 0;
+        let notes = s
+            .split(',')
+            .enumerate()
+            .map(|(idx, input)| {
+                let (note, start, duration) = parse_timed_note(input)
+                    .map_err(|err| ParseMIDINoteSequenceError::ParseMIDITimedNote(idx, err))?;
+                let timed_note = MIDITimedNote::new(note, start.unwrap_or(next_start), duration);
+                next_start = timed_note.end().ok_or_else(|| {
+                    let err = ParseMIDITimedNoteError::TickOverflow { input: input.to_string() };
+                    ParseMIDINoteSequenceError::ParseMIDITimedNote(idx, err)
+                })?;
+                Ok(timed_note)
+            })
+            .collect::<Result<Vec<MIDITimedNote>, ParseMIDINoteSequenceError>>()?;
+        Ok(Self(notes))
+    }
+}
+
+impl std::iter::FromIterator<MIDITimedNote> for MIDITimedNoteVec {
+    // Create MIDITimedNoteVec from iterator over MIDITimedNote
+    fn from_iter<I: IntoIterator<Item=MIDITimedNote>>(iter: I) -> Self {
+        Self(iter.into_iter().collect::<Vec<MIDITimedNote>>())
     }
 }
 
@@ -341,6 +1324,236 @@ mod tests {
         assert!(observed.is_err());
     }
 
+    #[test]
+    fn test_midi_note_from_str_enharmonic() {
+        let cases = vec![
+            (vec!["bsharp", "B#", "BSharp"], MIDINoteType::C, 5),
+            (vec!["c", "C"], MIDINoteType::C, 4),
+            (vec!["csharp", "c#", "dflat", "d♭", "db", "Db"], MIDINoteType::CSharp, 4),
+            (vec!["d"], MIDINoteType::D, 4),
+            (vec!["dsharp", "d#", "eflat", "e♭", "eb"], MIDINoteType::DSharp, 4),
+            (vec!["e", "fflat", "f♭", "fb", "Fb"], MIDINoteType::E, 4),
+            (vec!["esharp", "e#", "f", "E#"], MIDINoteType::F, 4),
+            (vec!["fsharp", "f#", "gflat", "g♭", "gb"], MIDINoteType::FSharp, 4),
+            (vec!["g"], MIDINoteType::G, 4),
+            (vec!["gsharp", "g#", "aflat", "a♭", "ab"], MIDINoteType::GSharp, 4),
+            (vec!["a"], MIDINoteType::A, 4),
+            (vec!["asharp", "a#", "bflat", "b♭", "bb"], MIDINoteType::ASharp, 4),
+            (vec!["b", "B"], MIDINoteType::B, 4),
+            (vec!["cflat", "c♭", "cb", "Cb", "CFlat"], MIDINoteType::B, 3),
+        ];
+        for (names, note_type, octave) in cases.into_iter() {
+            for name in names.into_iter() {
+                let observed = format!("{}:4", name).parse::<MIDINote>();
+                assert_eq!(Ok(MIDINote::new(note_type, octave)), observed, "{}", name);
+            }
+        }
+        // Octave carry is applied before the range check
+        assert_eq!(Ok(MIDINote::new(MIDINoteType::C, -1)), "B#:-2".parse::<MIDINote>());
+        assert!("Cb:-1".parse::<MIDINote>().is_err());
+        assert!("B#:9".parse::<MIDINote>().is_err());
+        // Octave carry does not overflow
+        let out_of_range = |note_type, octave| {
+            Err(ParseMIDINoteError::OutOfRange(MIDINoteRangeError::OutOfRange { note_type, octave }))
+        };
+        assert_eq!(out_of_range(MIDINoteType::C, i32::MAX), "B#:2147483647".parse::<MIDINote>());
+        assert_eq!(out_of_range(MIDINoteType::B, i32::MIN), "Cb:-2147483648".parse::<MIDINote>());
+        let pitch = "B#:2147483647".parse::<SpelledPitch>().unwrap();
+        assert_eq!(
+            Err(MIDINoteRangeError::OutOfRange { note_type: MIDINoteType::C, octave: i32::MAX }),
+            <MIDINote as std::convert::TryFrom<SpelledPitch>>::try_from(pitch),
+        );
+        // Notes with any octave can be compared and spelled
+        let highest = MIDINote { note_type: MIDINoteType::B, octave: i32::MAX };
+        let lowest = MIDINote { note_type: MIDINoteType::C, octave: i32::MIN };
+        assert!(lowest < highest);
+        assert_eq!(None, SpelledPitch::from_midi_note(&highest, &KeySpelling::new(-7)));
+        // Written octave saturates
+        assert_eq!(format!("Cb:{}", i32::MAX), highest.spell(&KeySpelling::new(-7)));
+    }
+
+    #[test]
+    fn test_spelled_pitch_from_str() {
+        use std::convert::TryFrom;
+
+        let cases = vec![
+            ("C:4", NoteLetter::C, Accidental::Natural, MIDINote::new(MIDINoteType::C, 4)),
+            ("C♮:4", NoteLetter::C, Accidental::Natural, MIDINote::new(MIDINoteType::C, 4)),
+            ("C♯:4", NoteLetter::C, Accidental::Sharp, MIDINote::new(MIDINoteType::CSharp, 4)),
+            ("Db:4", NoteLetter::D, Accidental::Flat, MIDINote::new(MIDINoteType::CSharp, 4)),
+            ("Ebb:4", NoteLetter::E, Accidental::DoubleFlat, MIDINote::new(MIDINoteType::D, 4)),
+            ("E𝄫:4", NoteLetter::E, Accidental::DoubleFlat, MIDINote::new(MIDINoteType::D, 4)),
+            ("Fx:4", NoteLetter::F, Accidental::DoubleSharp, MIDINote::new(MIDINoteType::G, 4)),
+            ("F𝄪:4", NoteLetter::F, Accidental::DoubleSharp, MIDINote::new(MIDINoteType::G, 4)),
+            ("Bx:4", NoteLetter::B, Accidental::DoubleSharp, MIDINote::new(MIDINoteType::CSharp, 5)),
+            ("Cbb:4", NoteLetter::C, Accidental::DoubleFlat, MIDINote::new(MIDINoteType::ASharp, 3)),
+            ("bb:4", NoteLetter::B, Accidental::Flat, MIDINote::new(MIDINoteType::ASharp, 4)),
+        ];
+        for (input, letter, accidental, note) in cases.into_iter() {
+            let pitch = input.parse::<SpelledPitch>().unwrap();
+            assert_eq!(SpelledPitch::new(letter, accidental, 4), pitch);
+            assert_eq!(Ok(note), MIDINote::try_from(pitch));
+            assert_eq!(Ok(note), input.parse::<MIDINote>());
+            assert_eq!(Ok(pitch), pitch.to_string().parse::<SpelledPitch>());
+            assert_eq!(Ok(pitch), format!("{:#}", pitch).parse::<SpelledPitch>());
+        }
+        assert_eq!(
+            Err(ParseMIDINoteError::UnknownNoteType(ParseMIDINoteTypeError::UnknownNoteType { input: "Rest".to_string() })),
+            "Rest:4".parse::<SpelledPitch>(),
+        );
+        assert!(MIDINote::try_from(SpelledPitch::new(NoteLetter::C, Accidental::Flat, -1)).is_err());
+    }
+
+    #[test]
+    fn test_spelled_pitch_from_midi_note() {
+        use std::convert::TryFrom;
+
+        for number in 0..=127u8 {
+            let note = MIDINote::try_from(number).unwrap();
+            for spelling in [&SharpSpelling as &dyn MIDINoteSpelling, &FlatSpelling].iter() {
+                let pitch = SpelledPitch::from_midi_note(&note, *spelling).unwrap();
+                assert_eq!(Ok(note), MIDINote::try_from(pitch));
+            }
+        }
+        let note = MIDINote::new(MIDINoteType::ASharp, 4);
+        assert_eq!(
+            Some(SpelledPitch::new(NoteLetter::B, Accidental::Flat, 4)),
+            SpelledPitch::from_midi_note(&note, &FlatSpelling),
+        );
+        assert_eq!(None, SpelledPitch::from_midi_note(&MIDINote::new(MIDINoteType::Rest, 4), &FlatSpelling));
+    }
+
+    #[test]
+    fn test_midi_note_from_str_scientific() {
+        let cases = vec![
+            ("C4", MIDINote::new(MIDINoteType::C, 4)),
+            ("C#4", MIDINote::new(MIDINoteType::CSharp, 4)),
+            ("c♯4", MIDINote::new(MIDINoteType::CSharp, 4)),
+            ("Bb-1", MIDINote::new(MIDINoteType::ASharp, -1)),
+            ("C-1", MIDINote::new(MIDINoteType::C, -1)),
+            ("G9", MIDINote::new(MIDINoteType::G, 9)),
+            ("B#3", MIDINote::new(MIDINoteType::C, 4)),
+            ("Fx4", MIDINote::new(MIDINoteType::G, 4)),
+            ("DFlat5", MIDINote::new(MIDINoteType::CSharp, 5)),
+            ("Rest4", MIDINote::new(MIDINoteType::Rest, 4)),
+        ];
+        for (input, expected) in cases.into_iter() {
+            assert_eq!(Ok(expected), input.parse::<MIDINote>(), "{}", input);
+            assert_eq!(Ok(expected), ScientificPitch(&expected).to_string().parse::<MIDINote>());
+            assert_eq!(Ok(expected), format!("{:#}", ScientificPitch(&expected)).parse::<MIDINote>());
+        }
+        for input in ["C", "4", "C#", "C#+4", "C 4", "-1"].iter() {
+            assert_eq!(
+                Err(ParseMIDINoteError::InvalidNoteFormat { input: input.to_string() }),
+                input.parse::<MIDINote>(),
+            );
+        }
+        assert!(matches!("C4.5".parse::<MIDINote>(), Err(ParseMIDINoteError::InvalidOctave(_))));
+        assert!(matches!("H4".parse::<MIDINote>(), Err(ParseMIDINoteError::UnknownNoteType(_))));
+        assert!(matches!("G#9".parse::<MIDINote>(), Err(ParseMIDINoteError::OutOfRange(_))));
+        assert_eq!(
+            Ok(SpelledPitch::new(NoteLetter::B, Accidental::Flat, -1)),
+            "Bb-1".parse::<SpelledPitch>(),
+        );
+    }
+
+    #[test]
+    fn test_midi_note_sequence_from_str_scientific() {
+        let expected = "C:4,E:4,G:4,C:5".parse::<MIDINoteVec>().unwrap();
+        assert_eq!(Ok(expected.clone()), "C4,E4,G4,C5".parse::<MIDINoteVec>());
+        assert_eq!(Ok(expected.clone()), "C4,E:4,G4,C:5".parse::<MIDINoteVec>());
+        assert_eq!(Ok(expected.clone()), ScientificPitch(&expected).to_string().parse::<MIDINoteVec>());
+        let set = "G4,C4,E4".parse::<MIDINoteSet>().unwrap();
+        assert_eq!("C4,E4,G4", ScientificPitch(&set).to_string());
+        assert_eq!(Ok(set.clone()), ScientificPitch(&set).to_string().parse::<MIDINoteSet>());
+    }
+
+    #[test]
+    fn test_midi_note_from_str_range() {
+        assert_eq!(Some(0), "C:-1".parse::<MIDINote>().unwrap().convert());
+        assert_eq!(Some(127), "G:9".parse::<MIDINote>().unwrap().convert());
+        assert_eq!(None, "Rest:12".parse::<MIDINote>().unwrap().convert());
+        for input in ["B:-2", "G#:9", "C:10"].iter() {
+            let observed = input.parse::<MIDINote>();
+            assert!(matches!(observed, Err(ParseMIDINoteError::OutOfRange(_))));
+        }
+    }
+
+    #[test]
+    fn test_midi_note_try_from_number() {
+        use std::convert::TryFrom;
+
+        for number in 0..=127u8 {
+            let note = MIDINote::try_from(number).unwrap();
+            assert_eq!(Some(number), note.convert());
+            assert_eq!(Ok(note), MIDINote::try_from(number as u32));
+            for spelling in [&SharpSpelling as &dyn MIDINoteSpelling, &FlatSpelling].iter() {
+                assert_eq!(Ok(note), format!("{}:{}", spelling.spell(note.note_type), note.octave).parse::<MIDINote>());
+            }
+        }
+        assert_eq!(Ok(MIDINote::new(MIDINoteType::C, -1)), MIDINote::try_from(0u8));
+        assert_eq!(
+            Err(MIDINoteRangeError::InvalidNoteNumber { number: 128 }),
+            MIDINote::try_from(128u8),
+        );
+        assert_eq!(
+            Err(MIDINoteRangeError::InvalidNoteNumber { number: 1000 }),
+            MIDINote::try_from(1000u32),
+        );
+    }
+
+    #[test]
+    fn test_midi_note_spell() {
+        let note = MIDINote::new(MIDINoteType::ASharp, 3);
+        assert_eq!("A#:3", note.spell(&SharpSpelling));
+        assert_eq!("Bb:3", note.spell(&FlatSpelling));
+        assert_eq!("A#:3", note.spell(&KeySpelling::new(0)));
+        assert_eq!("A#:3", note.spell(&KeySpelling::new(2)));
+        assert_eq!("Bb:3", note.spell(&KeySpelling::new(-2)));
+        assert_eq!("Rest:0", MIDINote::new(MIDINoteType::Rest, 0).spell(&FlatSpelling));
+    }
+
+    #[test]
+    fn test_key_spelling_scale() {
+        let spell_scale = |sharps_flats: i8, scale: &str| {
+            scale
+                .parse::<MIDINoteVec>()
+                .unwrap()
+                .iter()
+                .map(|note| note.spell(&KeySpelling::new(sharps_flats)))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        assert_eq!("C#:4,D#:4,E#:4,F#:4,G#:4,A#:4,B#:4", spell_scale(7, "C#:4,D#:4,F:4,F#:4,G#:4,A#:4,C:5"));
+        assert_eq!("Gb:3,Ab:3,Bb:3,Cb:4,Db:4,Eb:4,F:4", spell_scale(-6, "F#:3,G#:3,A#:3,B:3,C#:4,D#:4,F:4"));
+        assert_eq!("Cb:4,Db:4,Eb:4,Fb:4,Gb:4,Ab:4,Bb:4", spell_scale(-7, "B:3,C#:4,D#:4,E:4,F#:4,G#:4,A#:4"));
+        // Key signatures beyond seven sharps or flats are clamped
+        assert_eq!("E#:4", spell_scale(i8::MAX, "F:4"));
+        assert_eq!("Fb:4", spell_scale(i8::MIN, "E:4"));
+        // Notes outside of the scale
+        assert_eq!("D:4,G:4", spell_scale(7, "D:4,G:4"));
+        assert_eq!("D:4,Db:4", spell_scale(-1, "D:4,C#:4"));
+        // Spelled notes parse back to the same note
+        for sharps_flats in -7..=7 {
+            for number in 0..128u8 {
+                let note = <MIDINote as std::convert::TryFrom<u8>>::try_from(number).unwrap();
+                assert_eq!(Ok(note), note.spell(&KeySpelling::new(sharps_flats)).parse::<MIDINote>());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_midi_note_new_out_of_range() {
+        let _ = MIDINote::new(MIDINoteType::A, 9);
+    }
+
+    #[test]
+    fn test_midi_note_convert_out_of_range() {
+        let note = MIDINote { note_type: MIDINoteType::C, octave: 10 };
+        assert_eq!(None, note.convert());
+    }
+
     #[test]
     fn test_midi_note_set_from_str_valid_no_duplicate() {
         let observed = "C:4,D:4,E:4,F:4,F#:4,DFlat:5".parse::<MIDINoteSet>();
@@ -355,6 +1568,115 @@ mod tests {
         assert_eq!(expected, observed);
     }
 
+    #[test]
+    fn test_midi_note_display_round_trip() {
+        use std::convert::TryFrom;
+
+        let mut notes = (0..=127u8).map(|number| MIDINote::try_from(number).unwrap()).collect::<Vec<MIDINote>>();
+        notes.push(MIDINote::new(MIDINoteType::Rest, -1));
+        notes.push(MIDINote::new(MIDINoteType::Rest, 4));
+        for note in notes.iter() {
+            assert_eq!(Ok(*note), note.to_string().parse::<MIDINote>());
+            assert_eq!(Ok(*note), format!("{:#}", note).parse::<MIDINote>());
+        }
+        assert_eq!("D#:3", MIDINote::new(MIDINoteType::DSharp, 3).to_string());
+        assert_eq!("Eb:3", format!("{:#}", MIDINote::new(MIDINoteType::DSharp, 3)));
+        assert_eq!("Rest:4", MIDINote::new(MIDINoteType::Rest, 4).to_string());
+        assert_eq!("Bb", format!("{:#}", MIDINoteType::ASharp));
+
+        let sequence = MIDINoteVec(notes);
+        assert_eq!(Ok(sequence.clone()), sequence.to_string().parse::<MIDINoteVec>());
+        assert_eq!(Ok(sequence.clone()), format!("{:#}", sequence).parse::<MIDINoteVec>());
+        let set = sequence.iter().copied().collect::<std::collections::BTreeSet<MIDINote>>();
+        let set = MIDINoteSet(set);
+        assert_eq!(Ok(set.clone()), set.to_string().parse::<MIDINoteSet>());
+        assert_eq!(Ok(set.clone()), format!("{:#}", set).parse::<MIDINoteSet>());
+    }
+
+    #[test]
+    fn test_midi_note_display_empty() {
+        let sequence = MIDINoteVec(Vec::new());
+        assert_eq!("", sequence.to_string());
+        assert_eq!(Ok(sequence), "".parse::<MIDINoteVec>());
+        let set = MIDINoteSet(std::collections::BTreeSet::new());
+        assert_eq!("", set.to_string());
+        assert_eq!(Ok(set), "".parse::<MIDINoteSet>());
+    }
+
+    #[test]
+    fn test_midi_note_ord() {
+        let lower = MIDINote::new(MIDINoteType::D, 4);
+        let higher = MIDINote::new(MIDINoteType::C, 5);
+        assert!(lower < higher);
+        assert!(MIDINote::new(MIDINoteType::B, 3) < MIDINote::new(MIDINoteType::C, 4));
+        assert!(MIDINote::new(MIDINoteType::Rest, 9) < MIDINote::new(MIDINoteType::C, -1));
+        assert!(MIDINote::new(MIDINoteType::Rest, 0) < MIDINote::new(MIDINoteType::Rest, 1));
+        assert_eq!(std::cmp::Ordering::Equal, lower.cmp(&MIDINote::new(MIDINoteType::D, 4)));
+    }
+
+    #[test]
+    fn test_midi_note_set_pitch_order() {
+        let set = "C:5,G:3,Rest:4,D:4,B:3".parse::<MIDINoteSet>().unwrap();
+        let expected = "Rest:4,G:3,B:3,D:4,C:5".parse::<MIDINoteVec>().unwrap();
+        assert_eq!(expected, MIDINoteVec::from(&set));
+        assert_eq!(expected, MIDINoteVec::from(set));
+    }
+
+    #[test]
+    fn test_midi_note_set_from_str_range() {
+        let observed = "C:4..C:5".parse::<MIDINoteSet>().unwrap();
+        assert_eq!(13, observed.len());
+        assert_eq!(Some(&MIDINote::new(MIDINoteType::C, 4)), observed.iter().next());
+        assert_eq!(Some(&MIDINote::new(MIDINoteType::C, 5)), observed.iter().last());
+        assert_eq!(
+            Ok("C:-1,C#:-1,D:-1,G:9".parse::<MIDINoteSet>().unwrap()),
+            "C-1..D-1,G9..G9".parse::<MIDINoteSet>(),
+        );
+        assert_eq!(Err(ParseMIDINoteSequenceError::InvalidRange(1)), "C:4,C:5..C:4".parse::<MIDINoteSet>());
+        assert_eq!(Err(ParseMIDINoteSequenceError::InvalidRange(0)), "Rest:4..C:4".parse::<MIDINoteSet>());
+        assert!(matches!(
+            "C:4..".parse::<MIDINoteSet>(),
+            Err(ParseMIDINoteSequenceError::ParseMIDINote(0, _))
+        ));
+    }
+
+    #[test]
+    fn test_midi_note_set_from_str_scale() {
+        let expected = "D:4,E:4,F:4,G:4,A:4,B:4,C:5".parse::<MIDINoteSet>();
+        assert_eq!(expected, "D:dorian:4".parse::<MIDINoteSet>());
+        assert_eq!(expected, "scale(D dorian, 4)".parse::<MIDINoteSet>());
+        assert_eq!(
+            "C:4,D:4,E:4,F:4,G:4,A:4,B:4,C:5".parse::<MIDINoteSet>(),
+            "scale(C major, 4),C:5".parse::<MIDINoteSet>(),
+        );
+        assert_eq!(
+            "A:3,C:4,D:4,D#:4,E:4,G:4".parse::<MIDINoteSet>(),
+            "scale(A blues, 3)".parse::<MIDINoteSet>(),
+        );
+        assert_eq!(
+            "C:4,D:4,D#:4,F:4,G:4,G#:4,B:4".parse::<MIDINoteSet>(),
+            "C:Harmonic-Minor:4".parse::<MIDINoteSet>(),
+        );
+        assert_eq!(
+            Err(ParseMIDINoteSequenceError::ParseMIDIScale(
+                1,
+                ParseMIDIScaleError::UnknownScale { input: "bebop".to_string() },
+            )),
+            "C:4,C:bebop:4".parse::<MIDINoteSet>(),
+        );
+        assert_eq!(
+            Err(ParseMIDINoteSequenceError::ParseMIDIScale(
+                0,
+                ParseMIDIScaleError::InvalidScaleFormat { input: "scale(C, 4)".to_string() },
+            )),
+            "scale(C, 4)".parse::<MIDINoteSet>(),
+        );
+        assert!(matches!(
+            "G:major:9".parse::<MIDINoteSet>(),
+            Err(ParseMIDINoteSequenceError::ParseMIDIScale(0, ParseMIDIScaleError::OutOfRange(_)))
+        ));
+    }
+
     #[test]
     fn test_midi_note_set_from_str_valid_with_duplicate() {
         let observed = "C:4,C:4,D:5".parse::<MIDINoteSet>();
@@ -379,6 +1701,65 @@ mod tests {
         assert_eq!(expected, observed);
     }
 
+    #[test]
+    fn test_midi_timed_note_from_str_valid() {
+        let observed = "C#:5@3/2".parse::<MIDITimedNote>();
+        let expected = Ok(MIDITimedNote::new(MIDINote::new(MIDINoteType::CSharp, 5), 3, 2));
+        assert_eq!(expected, observed);
+        let observed = "C#:5/2".parse::<MIDITimedNote>();
+        let expected = Ok(MIDITimedNote::new(MIDINote::new(MIDINoteType::CSharp, 5), 0, 2));
+        assert_eq!(expected, observed);
+    }
+
+    #[test]
+    fn test_midi_timed_note_from_str_invalid_format() {
+        let input = "C#:5@3".to_string();
+        let observed = input.as_str().parse::<MIDITimedNote>();
+        let expected = Err(ParseMIDITimedNoteError::InvalidTimedNoteFormat { input });
+        assert_eq!(expected, observed);
+    }
+
+    #[test]
+    fn test_midi_timed_note_vec_from_str_invalid_note() {
+        let observed = "C:4/1,C:4@x/1".parse::<MIDITimedNoteVec>();
+        assert!(matches!(
+            observed,
+            Err(ParseMIDINoteSequenceError::ParseMIDITimedNote(1, ParseMIDITimedNoteError::InvalidTiming(_)))
+        ));
+    }
+
+    #[test]
+    fn test_midi_timed_note_vec_from_rhythm() {
+        let sequence = "C:4,D:4,E:4".parse::<MIDINoteVec>().unwrap();
+        let observed = MIDITimedNoteVec::from_rhythm(&sequence, &[3, 1, 4]);
+        let expected = "C:4/3,D:4/1,E:4/4".parse::<MIDITimedNoteVec>().unwrap();
+        assert_eq!(expected, observed);
+        assert_eq!(Some(8), observed.end());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_midi_timed_note_vec_from_rhythm_missing_duration() {
+        let sequence = "C:4,D:4,E:4".parse::<MIDINoteVec>().unwrap();
+        MIDITimedNoteVec::from_rhythm(&sequence, &[3, 1]);
+    }
+
+    #[test]
+    fn test_midi_timed_note_end_overflow() {
+        let note = MIDINote::new(MIDINoteType::C, 4);
+        assert_eq!(Some(u32::MAX), MIDITimedNote::new(note, u32::MAX - 1, 1).end());
+        assert_eq!(None, MIDITimedNote::new(note, u32::MAX, 1).end());
+        let input = "C:4@4294967295/1".to_string();
+        let observed = input.as_str().parse::<MIDITimedNote>();
+        assert_eq!(Err(ParseMIDITimedNoteError::TickOverflow { input }), observed);
+        let observed = "C:4@4294967290/5,D:4/1".parse::<MIDITimedNoteVec>();
+        let expected = Err(ParseMIDINoteSequenceError::ParseMIDITimedNote(
+            1,
+            ParseMIDITimedNoteError::TickOverflow { input: "D:4/1".to_string() },
+        ));
+        assert_eq!(expected, observed);
+    }
+
     #[test]
     fn test_midi_note_set_from_str_extra_comma() {
         // NOTE: Any invalid MIDINote in the input sequence